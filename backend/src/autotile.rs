@@ -0,0 +1,64 @@
+use crate::*;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum WallStyle {
+    Single,
+    Double,
+}
+
+impl WallStyle {
+    // Indexed by the mask of joined neighbors: north 1, south 2, west 4, east 8
+    pub(crate) fn glyphs(&self) -> [char; 16] {
+        match self {
+            WallStyle::Single => [
+                '■', '│', '│', '│', '─', '┘', '┐', '┤', '─', '└', '┌', '├', '─', '┴', '┬', '┼',
+            ],
+            WallStyle::Double => [
+                '■', '║', '║', '║', '═', '╝', '╗', '╣', '═', '╚', '╔', '╠', '═', '╩', '╦', '╬',
+            ],
+        }
+    }
+}
+
+// A wall is worth drawing if you could stand next to it, diagonals included
+fn visible_wall(map: &Map, pt: Point) -> bool {
+    match map.try_idx(pt) {
+        Some(idx) if map.is_wall(idx) => (-1..=1).any(|dy| {
+            (-1..=1).any(|dx| {
+                map.try_idx(pt + Point::new(dx, dy))
+                    .is_some_and(|n| !map.is_wall(n))
+            })
+        }),
+        _ => false,
+    }
+}
+
+// Returns a copy of the map for display only: walls that touch floor become
+// line-drawing glyphs joined to their visible neighbors, and solid rock with no
+// floor beside it is blanked out. Everything else is left alone.
+pub fn autotile(map: &Map, style: WallStyle) -> Map {
+    let glyphs = style.glyphs();
+    let mut rendered = map.clone();
+    for y in 0..map.height as i32 {
+        for x in 0..map.width as i32 {
+            let pt = Point::new(x, y);
+            let idx = map.idx(x, y);
+            if !map.is_wall(idx) {
+                continue;
+            }
+            if !visible_wall(map, pt) {
+                rendered.tiles[idx] = (to_cp437(' '), RGB::named(BLACK));
+                continue;
+            }
+
+            let mut mask = 0;
+            for (bit, delta) in [(0, -1), (0, 1), (-1, 0), (1, 0)].iter().enumerate() {
+                if visible_wall(map, pt + Point::new(delta.0, delta.1)) {
+                    mask |= 1 << bit;
+                }
+            }
+            rendered.tiles[idx] = (to_cp437(glyphs[mask]), RGB::named(GRAY));
+        }
+    }
+    rendered
+}
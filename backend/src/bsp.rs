@@ -0,0 +1,235 @@
+use crate::*;
+
+// Splits a rectangle into four equal quarters, each at least one tile across.
+// Neighboring quarters share their edge.
+pub fn subdivide(rect: Rect) -> [Rect; 4] {
+    let width = i32::abs(rect.x1 - rect.x2);
+    let height = i32::abs(rect.y1 - rect.y2);
+    let half_width = i32::max(width / 2, 1);
+    let half_height = i32::max(height / 2, 1);
+    [
+        Rect::with_size(rect.x1, rect.y1, half_width, half_height),
+        Rect::with_size(rect.x1, rect.y1 + half_height, half_width, half_height),
+        Rect::with_size(rect.x1 + half_width, rect.y1, half_width, half_height),
+        Rect::with_size(
+            rect.x1 + half_width,
+            rect.y1 + half_height,
+            half_width,
+            half_height,
+        ),
+    ]
+}
+
+// Splits a rectangle into quarters that meet at split. None if split isn't
+// strictly inside, since one of the quarters would have no width or height.
+pub fn subdivide_at(rect: Rect, split: Point) -> Option<[Rect; 4]> {
+    if split.x <= rect.x1 || split.x >= rect.x2 || split.y <= rect.y1 || split.y >= rect.y2 {
+        return None;
+    }
+    Some([
+        Rect::with_exact(rect.x1, rect.y1, split.x, split.y),
+        Rect::with_exact(rect.x1, split.y, split.x, rect.y2),
+        Rect::with_exact(split.x, rect.y1, rect.x2, split.y),
+        Rect::with_exact(split.x, split.y, rect.x2, rect.y2),
+    ])
+}
+
+// Leaves cover the whole area and their edges are the walls, so neighboring
+// rooms share a single tile of wall.
+pub struct BspInterior {
+    pub leaves: Vec<Rect>,
+    pub doors: Vec<Point>,
+}
+
+impl BspInterior {
+    // Keeps splitting area into quarters, somewhere near the middle, until they
+    // would be smaller than min_size or at random once past the first couple of
+    // levels. Every split gets doors joining its quarters, with loop_chance of
+    // a fourth door that makes a loop. min_size is at least 3, so every room has
+    // floor two tiles across and there is always somewhere for a door.
+    pub fn new(
+        area: Rect,
+        min_size: i32,
+        loop_chance: f32,
+        rng: &mut RandomNumberGenerator,
+    ) -> Self {
+        let min_size = i32::max(min_size, 3);
+        let mut leaves = Vec::new();
+        let mut splits = Vec::new();
+        let mut open_list = vec![(area, 0)];
+        while let Some((rect, depth)) = open_list.pop() {
+            let too_small = rect.width() < min_size * 2 || rect.height() < min_size * 2;
+            if too_small || (depth > 1 && rng.range(0, 3) == 0) {
+                leaves.push(rect);
+                continue;
+            }
+            let split = Point::new(
+                rng.range(rect.x1 + min_size, rect.x2 - min_size + 1),
+                rng.range(rect.y1 + min_size, rect.y2 - min_size + 1),
+            );
+            match subdivide_at(rect, split) {
+                Some(quarters) => {
+                    splits.push(quarters);
+                    open_list.extend(quarters.iter().map(|q| (*q, depth + 1)));
+                }
+                None => leaves.push(rect),
+            }
+        }
+
+        let mut interior = Self {
+            leaves,
+            doors: Vec::new(),
+        };
+
+        // Any three of the four walls between quarters join them all up; a wall
+        // with nowhere for a door is passed over for the next one
+        for quarters in splits.iter() {
+            let [top_left, bottom_left, top_right, bottom_right] = *quarters;
+            let mut walls = [
+                (top_left, top_right),
+                (bottom_left, bottom_right),
+                (top_left, bottom_left),
+                (top_right, bottom_right),
+            ];
+            for i in (1..walls.len()).rev() {
+                walls.swap(i, rng.range(0, i + 1));
+            }
+            let mut wanted = 3;
+            if rng.range(0.0, 1.0) < loop_chance {
+                wanted += 1;
+            }
+            for (a, b) in walls.iter() {
+                if wanted == 0 {
+                    break;
+                }
+                if let Some(door) = interior.pick_door(*a, *b, rng) {
+                    interior.doors.push(door);
+                    wanted -= 1;
+                }
+            }
+        }
+
+        interior
+    }
+
+    pub fn rooms(&self) -> Vec<Rect> {
+        self.leaves
+            .iter()
+            .map(|l| Rect::with_exact(l.x1 + 1, l.y1 + 1, l.x2 - 1, l.y2 - 1))
+            .collect()
+    }
+
+    fn is_floor(&self, pt: Point) -> bool {
+        self.leaves
+            .iter()
+            .any(|l| pt.x > l.x1 && pt.x < l.x2 && pt.y > l.y1 && pt.y < l.y2)
+    }
+
+    // A spot on the wall between two touching areas with floor on both sides
+    fn pick_door(&self, a: Rect, b: Rect, rng: &mut RandomNumberGenerator) -> Option<Point> {
+        let mut candidates = Vec::new();
+        if a.x2 == b.x1 {
+            for y in i32::max(a.y1, b.y1) + 1..i32::min(a.y2, b.y2) {
+                candidates.push((Point::new(a.x2, y), Point::new(1, 0)));
+            }
+        } else if a.y2 == b.y1 {
+            for x in i32::max(a.x1, b.x1) + 1..i32::min(a.x2, b.x2) {
+                candidates.push((Point::new(x, a.y2), Point::new(0, 1)));
+            }
+        }
+
+        let candidates: Vec<Point> = candidates
+            .into_iter()
+            .filter(|(pt, across)| self.is_floor(*pt - *across) && self.is_floor(*pt + *across))
+            .map(|(pt, _)| pt)
+            .collect();
+        rng.random_slice_entry(&candidates).copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quarters_are_never_empty() {
+        // Odd and tiny sizes used to leave a zero-width quarter on the right
+        for (w, h) in [(1, 1), (1, 5), (2, 2), (3, 7), (9, 4)] {
+            for q in subdivide(Rect::with_size(10, 10, w, h)).iter() {
+                assert!(
+                    q.width() >= 1 && q.height() >= 1,
+                    "{}x{} gave {:?}",
+                    w,
+                    h,
+                    q
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn quarters_are_equal() {
+        let quarters = subdivide(Rect::with_size(0, 0, 20, 10));
+        for q in quarters.iter() {
+            assert_eq!((q.width(), q.height()), (10, 5));
+        }
+        assert_eq!((quarters[3].x1, quarters[3].y1), (10, 5));
+    }
+
+    #[test]
+    fn no_split_on_the_edge() {
+        let rect = Rect::with_size(0, 0, 10, 10);
+        assert!(subdivide_at(rect, Point::new(10, 5)).is_none());
+        assert!(subdivide_at(rect, Point::new(5, 0)).is_none());
+        assert!(subdivide_at(rect, Point::new(12, 5)).is_none());
+        let quarters = subdivide_at(rect, Point::new(3, 7)).unwrap();
+        assert_eq!(quarters[3], Rect::with_exact(3, 7, 10, 10));
+    }
+
+    #[test]
+    fn interior_leaves_cover_the_area() {
+        let map = Map::new();
+        let area = Rect::with_exact(0, 0, map.width as i32 - 1, map.height as i32 - 1);
+        for min_size in [0, 1, 4] {
+            let mut rng = RandomNumberGenerator::seeded(min_size as u64);
+            let interior = BspInterior::new(area, min_size, 0.25, &mut rng);
+            let mut covered = vec![false; map.tiles.len()];
+            for leaf in interior.leaves.iter() {
+                assert!(leaf.width() >= 1 && leaf.height() >= 1);
+                for y in leaf.y1..=leaf.y2 {
+                    for x in leaf.x1..=leaf.x2 {
+                        covered[map.idx(x, y)] = true;
+                    }
+                }
+            }
+            assert!(covered.iter().all(|c| *c));
+        }
+    }
+
+    #[test]
+    fn interior_is_one_region() {
+        for min_size in [0, 1, 4, 6] {
+            for seed in 0..10 {
+                let mut map = Map::new();
+                let area = Rect::with_exact(0, 0, map.width as i32 - 1, map.height as i32 - 1);
+                let mut rng = RandomNumberGenerator::seeded(seed);
+                let interior = BspInterior::new(area, min_size, 0.0, &mut rng);
+                for room in interior.rooms().iter() {
+                    room.for_each(|p| map.set(p, to_cp437('#'), RGB::named(GREEN)));
+                }
+                for door in interior.doors.iter() {
+                    map.set(*door, to_cp437('+'), RGB::named(YELLOW));
+                }
+                let mut regions = map.regions();
+                regions.retain(|r| *r != usize::MAX);
+                assert!(!regions.is_empty());
+                assert!(
+                    regions.iter().all(|r| *r == regions[0]),
+                    "min_size {} seed {} is split up",
+                    min_size,
+                    seed
+                );
+            }
+        }
+    }
+}
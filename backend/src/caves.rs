@@ -0,0 +1,109 @@
+use crate::*;
+
+// Makes each tile a wall with a fill percent chance, and open floor otherwise
+pub fn random_fill(map: &mut Map, fill: i32, rng: &mut RandomNumberGenerator) {
+    map.tiles.iter_mut().for_each(|(t, c)| {
+        let roll = rng.range(0, 100);
+        if roll < fill {
+            *t = to_cp437('.');
+            *c = RGB::named(DARK_GRAY);
+        } else {
+            *t = to_cp437('#');
+            *c = RGB::named(GREEN);
+        }
+    });
+}
+
+fn count_neighbors(map: &Map, x: usize, y: usize) -> usize {
+    let mut n = 0;
+    for ty in -1..=1 {
+        for tx in -1..=1 {
+            if !(ty == 0 && tx == 0) {
+                if let Some(idx) = map.try_idx(Point::new(x as i32 + tx, y as i32 + ty)) {
+                    if map.tiles[idx].0 == to_cp437('.') {
+                        n += 1;
+                    }
+                }
+            }
+        }
+    }
+    n
+}
+
+// One cellular automata pass: tiles with no walls around them, or five or more,
+// become walls and everything else opens up. The map's edge is left alone.
+pub fn cellular_iterate(map: &mut Map) {
+    let map_copy = map.clone();
+    for y in 1..map.height - 1 {
+        for x in 1..map.width - 1 {
+            let neighbors = count_neighbors(&map_copy, x, y);
+            if neighbors == 0 {
+                map.set(Point::new(x, y), to_cp437('.'), RGB::named(DARK_GRAY));
+            } else if neighbors < 5 {
+                map.set(Point::new(x, y), to_cp437('#'), RGB::named(GREEN));
+            } else {
+                map.set(Point::new(x, y), to_cp437('.'), RGB::named(DARK_GRAY));
+            }
+        }
+    }
+}
+
+// Staggers from start for up to 200 steps, opening every tile it passes
+pub fn drunkard_walk(map: &mut Map, start: Point, rng: &mut RandomNumberGenerator) {
+    let mut steps = 0;
+    let mut pos = start;
+    loop {
+        let delta = match rng.range(0, 4) {
+            0 => Point::new(-1, 0),
+            1 => Point::new(1, 0),
+            2 => Point::new(0, -1),
+            _ => Point::new(0, 1),
+        };
+        pos += delta;
+        if let Some(_idx) = map.try_idx(pos) {
+            map.set(pos, to_cp437('#'), RGB::named(GREEN));
+        } else {
+            break;
+        }
+
+        steps += 1;
+        if steps > 200 {
+            break;
+        }
+    }
+}
+
+// Sends diggers out from random open tiles, or the middle of the map if there
+// aren't any, until at least target (a fraction of the map) is open
+pub fn dig_until_open(
+    map: &mut Map,
+    target: f32,
+    rng: &mut RandomNumberGenerator,
+    frames: &mut dyn SnapshotRecorder,
+) {
+    let mut i = 1;
+    while (map.tiles.iter().filter(|t| t.0 == to_cp437('#')).count() as f32)
+        < map.tiles.len() as f32 * target
+    {
+        let open_tiles: Vec<usize> = map
+            .tiles
+            .iter()
+            .enumerate()
+            .filter(|(_, t)| t.0 == to_cp437('#'))
+            .map(|(i, _)| i)
+            .collect();
+        match rng.random_slice_entry(&open_tiles) {
+            Some(start) => {
+                let start = map.point(*start);
+                drunkard_walk(map, start, rng);
+                frames.record(map, format!("Drunken Digger {}", i));
+            }
+            None => {
+                let middle = Point::new(map.width / 2, map.height / 2);
+                drunkard_walk(map, middle, rng);
+                frames.record(map, "First Drunken Digger".to_string());
+            }
+        }
+        i += 1;
+    }
+}
@@ -0,0 +1,499 @@
+use crate::*;
+use std::collections::HashMap;
+use std::io::Write;
+use std::str::FromStr;
+use std::sync::Mutex;
+
+const USAGE: &str = "Options:
+  --seed <n>                 Seed every random number generator
+  --width <n>                Map width, at least 60 (default 80)
+  --height <n>               Map height, at least 40 (default 50)
+  --set <name>=<value>       Set a builder parameter, e.g. --set rooms=40
+  --config <file>            Read parameters and levels from a file
+  --level <name>             Build one chapter or [[level]] (launcher only)
+  --headless                 Build without opening a window
+  --export <txt|csv>         Write frames, then any entities, to stdout
+  --frames <none|final|all>  Which frames to keep and export (default final)
+  --autotile <single|double> Export walls as joined lines, like W in the viewer
+  --script <file>            Run the talk from a script (launcher only)
+  --help                     Show this message";
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum FrameSelection {
+    None,
+    Final,
+    All,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum ExportFormat {
+    // One character per tile
+    Text,
+    // Glyph codes, comma separated
+    Csv,
+}
+
+#[derive(Debug)]
+pub enum CliError {
+    MissingValue(String),
+    BadValue { flag: String, value: String },
+    UnknownFlag(String),
+    Config(ConfigError),
+}
+
+impl std::fmt::Display for CliError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            CliError::MissingValue(flag) => write!(f, "{} needs a value\n{}", flag, USAGE),
+            CliError::BadValue { flag, value } => {
+                write!(f, "\"{}\" isn't valid for {}\n{}", value, flag, USAGE)
+            }
+            CliError::UnknownFlag(flag) => write!(f, "Unknown option {}\n{}", flag, USAGE),
+            CliError::Config(e) => write!(f, "{}", e),
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+// Below this the chapters' rooms, prefabs and towns stop fitting
+pub const MIN_WIDTH: usize = 60;
+pub const MIN_HEIGHT: usize = 40;
+
+pub struct CliOptions {
+    pub seed: Option<u64>,
+    pub width: usize,
+    pub height: usize,
+    pub params: HashMap<String, String>,
+    // The --config file; its [params] are already in params
+    pub config: Option<Config>,
+    pub level: Option<String>,
+    pub headless: bool,
+    pub export: Option<ExportFormat>,
+    pub frames: FrameSelection,
+    pub autotile: Option<WallStyle>,
+    pub script: Option<String>,
+    pub help: bool,
+}
+
+impl Default for CliOptions {
+    fn default() -> Self {
+        Self {
+            seed: None,
+            width: DEFAULT_WIDTH,
+            height: DEFAULT_HEIGHT,
+            params: HashMap::new(),
+            config: None,
+            level: None,
+            headless: false,
+            export: None,
+            frames: FrameSelection::Final,
+            autotile: None,
+            script: None,
+            help: false,
+        }
+    }
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: Option<String>) -> Result<T, CliError> {
+    let value = value.ok_or_else(|| CliError::MissingValue(flag.to_string()))?;
+    value.parse().map_err(|_| CliError::BadValue {
+        flag: flag.to_string(),
+        value,
+    })
+}
+
+fn parse_size(flag: &str, value: Option<String>, min: usize) -> Result<usize, CliError> {
+    let size: usize = parse_value(flag, value)?;
+    if size < min {
+        return Err(CliError::BadValue {
+            flag: flag.to_string(),
+            value: size.to_string(),
+        });
+    }
+    Ok(size)
+}
+
+impl CliOptions {
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, CliError> {
+        let mut options = Self::default();
+        let mut args = args.into_iter();
+        while let Some(flag) = args.next() {
+            match flag.as_str() {
+                "--seed" => options.seed = Some(parse_value(&flag, args.next())?),
+                "--width" => options.width = parse_size(&flag, args.next(), MIN_WIDTH)?,
+                "--height" => options.height = parse_size(&flag, args.next(), MIN_HEIGHT)?,
+                "--set" => {
+                    let value: String = parse_value(&flag, args.next())?;
+                    match value.split_once('=') {
+                        Some((name, setting)) => {
+                            options
+                                .params
+                                .insert(name.trim().to_string(), setting.trim().to_string());
+                        }
+                        None => return Err(CliError::BadValue { flag, value }),
+                    }
+                }
+                "--config" => {
+                    let path: String = parse_value(&flag, args.next())?;
+                    options.config = Some(Config::load(&path).map_err(CliError::Config)?);
+                }
+                "--level" => options.level = Some(parse_value(&flag, args.next())?),
+                "--headless" => options.headless = true,
+                "--export" => {
+                    let value: String = parse_value(&flag, args.next())?;
+                    options.export = match value.as_str() {
+                        "txt" => Some(ExportFormat::Text),
+                        "csv" => Some(ExportFormat::Csv),
+                        _ => return Err(CliError::BadValue { flag, value }),
+                    };
+                }
+                "--frames" => {
+                    let value: String = parse_value(&flag, args.next())?;
+                    options.frames = match value.as_str() {
+                        "none" => FrameSelection::None,
+                        "final" => FrameSelection::Final,
+                        "all" => FrameSelection::All,
+                        _ => return Err(CliError::BadValue { flag, value }),
+                    };
+                }
+                "--autotile" => {
+                    let value: String = parse_value(&flag, args.next())?;
+                    options.autotile = match value.as_str() {
+                        "single" => Some(WallStyle::Single),
+                        "double" => Some(WallStyle::Double),
+                        _ => return Err(CliError::BadValue { flag, value }),
+                    };
+                }
+                "--script" => options.script = Some(parse_value(&flag, args.next())?),
+                "--help" | "-h" => options.help = true,
+                _ => return Err(CliError::UnknownFlag(flag)),
+            }
+        }
+
+        // --set wins over the file, wherever it comes on the command line
+        if let Some(config) = &options.config {
+            for (name, value) in config.params() {
+                options.params.entry(name).or_insert(value);
+            }
+        }
+        Ok(options)
+    }
+
+    // The program's own arguments. Scripts need a failing exit code, and people
+    // need the message rather than the error's Debug output, so a bad command
+    // line exits here.
+    pub fn from_args() -> Self {
+        match Self::parse(std::env::args().skip(1)) {
+            Ok(options) => options,
+            Err(e) => {
+                eprintln!("{}", e);
+                std::process::exit(2);
+            }
+        }
+    }
+
+    pub fn usage() -> &'static str {
+        USAGE
+    }
+
+    // Makes the seed, map size and parameters visible to new_rng, Map::new and
+    // param
+    pub fn apply(&self) {
+        *NEXT_SEED.lock().unwrap() = self.seed;
+        *MAP_SIZE.lock().unwrap() = (self.width, self.height);
+        *PARAMS.lock().unwrap() = Some(self.params.clone());
+    }
+}
+
+static NEXT_SEED: Mutex<Option<u64>> = Mutex::new(None);
+static PARAMS: Mutex<Option<HashMap<String, String>>> = Mutex::new(None);
+static MAP_SIZE: Mutex<(usize, usize)> = Mutex::new((DEFAULT_WIDTH, DEFAULT_HEIGHT));
+
+// The width and height every new map gets
+pub fn map_size() -> (usize, usize) {
+    *MAP_SIZE.lock().unwrap()
+}
+
+// Builders get their generators here, so --seed makes a whole run repeatable.
+// Each one gets the next seed along, so two generators in one builder differ.
+pub fn new_rng() -> RandomNumberGenerator {
+    let mut next_seed = NEXT_SEED.lock().unwrap();
+    match next_seed.as_mut() {
+        Some(seed) => {
+            let rng = RandomNumberGenerator::seeded(*seed);
+            *seed = seed.wrapping_add(1);
+            rng
+        }
+        None => RandomNumberGenerator::new(),
+    }
+}
+
+// A builder parameter from --set, or the default if it wasn't given or
+// doesn't parse
+pub fn param<T: FromStr>(name: &str, default: T) -> T {
+    let params = PARAMS.lock().unwrap();
+    match params.as_ref().and_then(|p| p.get(name)) {
+        Some(value) => value.parse().unwrap_or_else(|_| {
+            eprintln!("Ignoring {}={}, which isn't valid", name, value);
+            default
+        }),
+        None => default,
+    }
+}
+
+// Runs f with extra parameters on top of the ones already set, then puts the
+// old ones back
+pub fn with_params<T>(params: &HashMap<String, String>, f: impl FnOnce() -> T) -> T {
+    let saved = {
+        let mut current = PARAMS.lock().unwrap();
+        let saved = current.clone();
+        let merged = current.get_or_insert_with(HashMap::new);
+        merged.extend(params.iter().map(|(k, v)| (k.clone(), v.clone())));
+        saved
+    };
+    let result = f();
+    *PARAMS.lock().unwrap() = saved;
+    result
+}
+
+// The frames a selection keeps, rebuilt from a recorder
+pub(crate) fn selected_frames(
+    recorder: &FrameRecorder,
+    selection: FrameSelection,
+) -> Vec<(Map, String)> {
+    let range = match selection {
+        FrameSelection::None => 0..0,
+        FrameSelection::Final => recorder.len().saturating_sub(1)..recorder.len(),
+        FrameSelection::All => 0..recorder.len(),
+    };
+    range
+        .map(|i| (recorder.frame(i), recorder.caption(i).to_string()))
+        .collect()
+}
+
+pub fn export_frames(
+    frames: &[(Map, String)],
+    format: ExportFormat,
+    style: Option<WallStyle>,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    for (map, caption) in frames.iter() {
+        let tiled;
+        let map = match style {
+            Some(style) => {
+                tiled = autotile(map, style);
+                &tiled
+            }
+            None => map,
+        };
+        writeln!(out, "{}", caption)?;
+        for y in 0..map.height {
+            let row = &map.tiles[y * map.width..(y + 1) * map.width];
+            let line = match format {
+                ExportFormat::Text => row.iter().map(|t| to_char(t.0 as u8)).collect(),
+                ExportFormat::Csv => row
+                    .iter()
+                    .map(|t| t.0.to_string())
+                    .collect::<Vec<String>>()
+                    .join(","),
+            };
+            writeln!(out, "{}", line)?;
+        }
+        writeln!(out)?;
+    }
+    Ok(())
+}
+
+// Follows the frames with whatever the builder spawned, one entity per line
+pub fn export_entities(
+    entities: &[Entity],
+    format: ExportFormat,
+    out: &mut impl Write,
+) -> std::io::Result<()> {
+    if entities.is_empty() {
+        return Ok(());
+    }
+    writeln!(out, "Entities")?;
+    for e in entities.iter() {
+        match format {
+            ExportFormat::Text => writeln!(
+                out,
+                "{} {} {} {}",
+                to_char(e.glyph as u8),
+                e.position.x,
+                e.position.y,
+                e.name
+            )?,
+            ExportFormat::Csv => writeln!(
+                out,
+                "{},{},{},{}",
+                e.glyph, e.position.x, e.position.y, e.name
+            )?,
+        }
+    }
+    writeln!(out)
+}
+
+// Builds without a window, reporting how long it took on stderr
+pub fn run_headless(mut builder: Box<dyn MapGen>, options: &CliOptions) -> BError {
+    let started = std::time::Instant::now();
+    builder.setup();
+    let frames = match options.frames {
+        FrameSelection::None => {
+            builder.build(&mut NullRecorder);
+            Vec::new()
+        }
+        FrameSelection::Final => {
+            let mut latest = LatestRecorder::default();
+            builder.build(&mut latest);
+            latest.latest.into_iter().collect()
+        }
+        FrameSelection::All => {
+            let mut recorder = FrameRecorder::new();
+            builder.build(&mut recorder);
+            selected_frames(&recorder, FrameSelection::All)
+        }
+    };
+    eprintln!(
+        "Built in {:.1}ms, kept {} frames",
+        started.elapsed().as_secs_f32() * 1000.0,
+        frames.len()
+    );
+
+    if let Some(format) = options.export {
+        let mut out = std::io::stdout().lock();
+        export_frames(&frames, format, options.autotile, &mut out)?;
+        export_entities(&builder.entities(), format, &mut out)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<CliOptions, CliError> {
+        CliOptions::parse(args.iter().map(|a| a.to_string()))
+    }
+
+    #[test]
+    fn no_arguments_gives_the_defaults() {
+        let options = parse(&[]).unwrap();
+        assert_eq!(options.seed, None);
+        assert_eq!(
+            (options.width, options.height),
+            (DEFAULT_WIDTH, DEFAULT_HEIGHT)
+        );
+        assert!(options.params.is_empty());
+        assert!(!options.headless && !options.help);
+        assert!(options.export.is_none() && options.autotile.is_none());
+        assert!(options.frames == FrameSelection::Final);
+    }
+
+    #[test]
+    fn every_flag_is_read() {
+        let options = parse(&[
+            "--seed",
+            "7",
+            "--width",
+            "120",
+            "--height",
+            "45",
+            "--set",
+            "rooms = 40",
+            "--headless",
+            "--export",
+            "csv",
+            "--frames",
+            "all",
+            "--autotile",
+            "double",
+            "--script",
+            "talk.script",
+            "--level",
+            "Open Caverns",
+        ])
+        .unwrap();
+        assert_eq!(options.seed, Some(7));
+        assert_eq!((options.width, options.height), (120, 45));
+        assert_eq!(options.params.get("rooms").map(String::as_str), Some("40"));
+        assert!(options.headless);
+        assert!(options.export == Some(ExportFormat::Csv));
+        assert!(options.frames == FrameSelection::All);
+        assert!(options.autotile == Some(WallStyle::Double));
+        assert_eq!(options.script.as_deref(), Some("talk.script"));
+        assert_eq!(options.level.as_deref(), Some("Open Caverns"));
+    }
+
+    #[test]
+    fn bad_command_lines_are_errors() {
+        assert!(matches!(parse(&["--seed"]), Err(CliError::MissingValue(_))));
+        assert!(matches!(
+            parse(&["--seed", "x"]),
+            Err(CliError::BadValue { .. })
+        ));
+        assert!(matches!(
+            parse(&["--set", "rooms"]),
+            Err(CliError::BadValue { .. })
+        ));
+        assert!(matches!(
+            parse(&["--frames", "some"]),
+            Err(CliError::BadValue { .. })
+        ));
+        assert!(matches!(
+            parse(&["--width", "59"]),
+            Err(CliError::BadValue { .. })
+        ));
+        assert!(matches!(
+            parse(&["--height", "-1"]),
+            Err(CliError::BadValue { .. })
+        ));
+        assert!(matches!(
+            parse(&["--depth", "3"]),
+            Err(CliError::UnknownFlag(_))
+        ));
+    }
+
+    #[test]
+    fn set_wins_over_the_config_file() {
+        let path = std::env::temp_dir().join(format!("cli-test-{}.toml", std::process::id()));
+        std::fs::write(
+            &path,
+            "[params]\nrooms = 10\nfill = 60\n\n[[level]]\nname = \"Caves\"\nsteps = [{ step = \"cull\" }]\n",
+        )
+        .unwrap();
+        let path = path.to_str().unwrap();
+        let options = parse(&["--set", "rooms=40", "--config", path]).unwrap();
+        std::fs::remove_file(path).unwrap();
+        assert_eq!(options.params.get("rooms").map(String::as_str), Some("40"));
+        assert_eq!(options.params.get("fill").map(String::as_str), Some("60"));
+        // The rest of the file is kept for whichever program uses it
+        assert_eq!(options.config.unwrap().levels[0].name, "Caves");
+    }
+
+    #[test]
+    fn entities_follow_the_frames() {
+        let entities = [Entity {
+            name: "Goblin".to_string(),
+            glyph: to_cp437('g'),
+            color: RGB::named(GREEN),
+            position: Point::new(3, 4),
+        }];
+        let mut out = Vec::new();
+        export_entities(&entities, ExportFormat::Text, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Entities\ng 3 4 Goblin\n\n"
+        );
+        let mut out = Vec::new();
+        export_entities(&entities, ExportFormat::Csv, &mut out).unwrap();
+        assert_eq!(
+            String::from_utf8(out).unwrap(),
+            "Entities\n103,3,4,Goblin\n\n"
+        );
+        let mut out = Vec::new();
+        export_entities(&[], ExportFormat::Text, &mut out).unwrap();
+        assert!(out.is_empty());
+    }
+}
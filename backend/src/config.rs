@@ -0,0 +1,357 @@
+use crate::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io(std::io::Error),
+    Parse(toml::de::Error),
+    NoSteps(String),
+    UnknownChapter(String),
+    // Tables the program that loaded the file has no use for
+    Unused(&'static str),
+}
+
+impl std::fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ConfigError::Io(e) => write!(f, "Couldn't read the config: {}", e),
+            ConfigError::Parse(e) => write!(f, "Couldn't parse the config: {}", e),
+            ConfigError::NoSteps(name) => write!(f, "Level \"{}\" has no steps", name),
+            ConfigError::UnknownChapter(name) => {
+                write!(
+                    f,
+                    "There's no chapter called \"{}\" to set params for",
+                    name
+                )
+            }
+            ConfigError::Unused(tables) => write!(
+                f,
+                "{} tables only work in the launcher: cargo run -- --config <file>",
+                tables
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+impl From<std::io::Error> for ConfigError {
+    fn from(e: std::io::Error) -> Self {
+        ConfigError::Io(e)
+    }
+}
+
+impl From<toml::de::Error> for ConfigError {
+    fn from(e: toml::de::Error) -> Self {
+        ConfigError::Parse(e)
+    }
+}
+
+// One stage of a level's pipeline. They all work on the same map, in order,
+// and anything left out falls back to what the chapters use.
+#[derive(Clone, Deserialize)]
+#[serde(tag = "step", rename_all = "snake_case", deny_unknown_fields)]
+pub enum Step {
+    // Percent chance of each tile being a wall; everything else is opened up
+    Noise {
+        fill: Option<i32>,
+    },
+    Cellular {
+        iterations: Option<usize>,
+    },
+    // Diggers keep going until this fraction of the map is open
+    Drunkard {
+        open: Option<f32>,
+    },
+    Rooms {
+        count: Option<usize>,
+        min_size: Option<i32>,
+        max_size: Option<i32>,
+    },
+    // Carved through solid rock only, like Maze::fill
+    Maze {
+        algorithm: Option<MazeAlgorithm>,
+        braid: Option<f32>,
+        corridor_width: Option<usize>,
+    },
+    BspInterior {
+        min_size: Option<i32>,
+        loop_chance: Option<f32>,
+    },
+    // Braces, so a field given to it by mistake is an error like elsewhere
+    Cull {},
+}
+
+#[derive(Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct LevelConfig {
+    pub name: String,
+    #[serde(default)]
+    pub description: String,
+    pub steps: Vec<Step>,
+}
+
+// A config file: a [params] table, read by param() just like --set, any number
+// of [chapter."Name"] tables that only apply to that chapter, and any number of
+// [[level]] pipelines for the launcher.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    #[serde(default)]
+    params: HashMap<String, toml::Value>,
+    #[serde(default, rename = "chapter")]
+    chapters: HashMap<String, HashMap<String, toml::Value>>,
+    #[serde(default, rename = "level")]
+    pub levels: Vec<LevelConfig>,
+}
+
+impl Config {
+    pub fn parse(text: &str) -> Result<Self, ConfigError> {
+        let config: Config = toml::from_str(text)?;
+        if let Some(level) = config.levels.iter().find(|l| l.steps.is_empty()) {
+            return Err(ConfigError::NoSteps(level.name.clone()));
+        }
+        Ok(config)
+    }
+
+    pub fn load(path: &str) -> Result<Self, ConfigError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    // A single chapter's binary only reads [params], so anything else in the
+    // file is an error rather than quietly doing nothing
+    pub fn params_only(&self) -> Result<(), ConfigError> {
+        if !self.levels.is_empty() {
+            return Err(ConfigError::Unused("[[level]]"));
+        }
+        if !self.chapters.is_empty() {
+            return Err(ConfigError::Unused("[chapter]"));
+        }
+        Ok(())
+    }
+
+    // As strings, the way param() reads them
+    pub fn params(&self) -> HashMap<String, String> {
+        as_params(&self.params)
+    }
+
+    // Each chapter name with its own params
+    pub fn chapter_params(&self) -> Vec<(String, HashMap<String, String>)> {
+        self.chapters
+            .iter()
+            .map(|(name, table)| (name.clone(), as_params(table)))
+            .collect()
+    }
+}
+
+fn as_params(table: &HashMap<String, toml::Value>) -> HashMap<String, String> {
+    table
+        .iter()
+        .map(|(name, value)| {
+            let value = match value {
+                toml::Value::String(s) => s.clone(),
+                _ => value.to_string(),
+            };
+            (name.clone(), value)
+        })
+        .collect()
+}
+
+// Builds a level from its config, recording a frame after every step
+pub struct Pipeline {
+    level: LevelConfig,
+}
+
+impl Pipeline {
+    pub fn new(level: LevelConfig) -> Box<Self> {
+        Box::new(Self { level })
+    }
+}
+
+impl MapGen for Pipeline {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+        let mut map = Map::new();
+        frames.record(&map, "Start Solid".to_string());
+
+        for step in self.level.steps.iter() {
+            match step {
+                Step::Noise { fill } => {
+                    let fill = fill.unwrap_or(55);
+                    random_fill(&mut map, fill, &mut rng);
+                    frames.record(&map, format!("Random Noise - {}% Walls", fill));
+                }
+                Step::Cellular { iterations } => {
+                    for i in 0..iterations.unwrap_or(10) {
+                        cellular_iterate(&mut map);
+                        frames.record(&map, format!("Iteration {}", i + 1));
+                    }
+                }
+                Step::Drunkard { open } => {
+                    let target = open.unwrap_or(1.0 / 3.0).min(0.9);
+                    dig_until_open(&mut map, target, &mut rng, frames);
+                }
+                Step::Rooms {
+                    count,
+                    min_size,
+                    max_size,
+                } => {
+                    // Anything bigger couldn't fit on the map
+                    let min_size = min_size.unwrap_or(2).clamp(1, 20);
+                    let max_size = max_size.unwrap_or(10).clamp(min_size + 1, 24);
+                    let rooms = random_rooms(
+                        &mut map,
+                        count.unwrap_or(20),
+                        min_size,
+                        max_size,
+                        &mut rng,
+                        frames,
+                    );
+                    join_rooms(&mut map, &rooms, &mut rng, frames);
+                }
+                Step::Maze {
+                    algorithm,
+                    braid,
+                    corridor_width,
+                } => {
+                    let mut maze =
+                        Maze::new(algorithm.unwrap_or(MazeAlgorithm::RecursiveBacktracker));
+                    maze.braid = braid.unwrap_or(0.0);
+                    maze.corridor_width = corridor_width.unwrap_or(1);
+                    maze.fill(&mut map, &mut rng, frames);
+                    frames.record(&map, "Maze".to_string());
+                }
+                Step::BspInterior {
+                    min_size,
+                    loop_chance,
+                } => {
+                    let interior = BspInterior::new(
+                        Rect::with_exact(0, 0, map.width as i32 - 1, map.height as i32 - 1),
+                        min_size.unwrap_or(4),
+                        loop_chance.unwrap_or(0.25),
+                        &mut rng,
+                    );
+                    for (i, room) in interior.rooms().iter().enumerate() {
+                        room.for_each(|p| {
+                            map.set(p, to_cp437('#'), iteration_color(i));
+                        });
+                    }
+                    for door in interior.doors.iter() {
+                        map.set(*door, to_cp437('+'), RGB::named(YELLOW));
+                    }
+                    frames.record(&map, "BSP Interior".to_string());
+                }
+                Step::Cull {} => {
+                    map.cull_disconnected();
+                    frames.record(&map, "Cull Unreachable Areas".to_string());
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn levels_and_their_steps_are_read() {
+        let config = Config::parse(
+            r#"
+            [[level]]
+            name = "Caves"
+            steps = [
+                { step = "noise", fill = 50 },
+                { step = "cellular" },
+                { step = "maze", algorithm = "kruskal" },
+                { step = "cull" },
+            ]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(config.levels.len(), 1);
+        let level = &config.levels[0];
+        assert_eq!(level.name, "Caves");
+        assert_eq!(level.description, "");
+        assert!(matches!(level.steps[0], Step::Noise { fill: Some(50) }));
+        assert!(matches!(
+            level.steps[1],
+            Step::Cellular { iterations: None }
+        ));
+        assert!(matches!(
+            level.steps[2],
+            Step::Maze {
+                algorithm: Some(MazeAlgorithm::Kruskal),
+                ..
+            }
+        ));
+        assert!(matches!(level.steps[3], Step::Cull {}));
+    }
+
+    #[test]
+    fn params_read_the_way_param_does() {
+        let config = Config::parse(
+            r#"
+            [params]
+            rooms = 20
+            open = 0.5
+            style = "double"
+
+            [chapter."Simple Map"]
+            rooms = 5
+            "#,
+        )
+        .unwrap();
+        let params = config.params();
+        assert_eq!(params["rooms"], "20");
+        assert_eq!(params["open"], "0.5");
+        assert_eq!(params["style"], "double");
+        let chapters = config.chapter_params();
+        assert_eq!(chapters.len(), 1);
+        assert_eq!(chapters[0].0, "Simple Map");
+        assert_eq!(chapters[0].1["rooms"], "5");
+    }
+
+    #[test]
+    fn mistakes_are_errors() {
+        let no_steps = "[[level]]\nname = \"Empty\"\nsteps = []";
+        assert!(
+            matches!(Config::parse(no_steps), Err(ConfigError::NoSteps(name)) if name == "Empty")
+        );
+
+        // An unknown step, a misspelled field, a field on a step without any,
+        // and a table that isn't params, chapter or level
+        let mistakes = [
+            "[[level]]\nname = \"X\"\nsteps = [{ step = \"lava\" }]",
+            "[[level]]\nname = \"X\"\nsteps = [{ step = \"noise\", fil = 1 }]",
+            "[[level]]\nname = \"X\"\nsteps = [{ step = \"cull\", fill = 1 }]",
+            "[rooms]\ncount = 3",
+        ];
+        for text in mistakes.iter() {
+            assert!(
+                matches!(Config::parse(text), Err(ConfigError::Parse(_))),
+                "{}",
+                text
+            );
+        }
+    }
+
+    #[test]
+    fn only_params_can_go_to_a_single_chapter() {
+        let params = Config::parse("[params]\nrooms = 5").unwrap();
+        assert!(params.params_only().is_ok());
+        let level = Config::parse("[[level]]\nname = \"X\"\nsteps = [{ step = \"cull\" }]");
+        assert!(matches!(
+            level.unwrap().params_only(),
+            Err(ConfigError::Unused("[[level]]"))
+        ));
+        let chapter = Config::parse("[chapter.\"Simple Map\"]\nrooms = 5").unwrap();
+        assert!(matches!(
+            chapter.params_only(),
+            Err(ConfigError::Unused("[chapter]"))
+        ));
+    }
+}
@@ -0,0 +1,113 @@
+use crate::*;
+
+pub struct Branch {
+    pub root: usize,
+    pub tiles: Vec<usize>,
+    pub depth: f32,
+}
+
+pub struct CriticalPath {
+    pub steps: Vec<usize>,
+    pub rooms: Vec<usize>,
+    pub distance: Vec<f32>,
+    pub branches: Vec<Branch>,
+}
+
+impl CriticalPath {
+    pub fn on_path(&self, idx: usize) -> bool {
+        self.distance[idx] == 0.0
+    }
+
+    pub fn regions(&self, membership: &[usize]) -> Vec<usize> {
+        let mut regions: Vec<usize> = self.steps.iter().map(|idx| membership[*idx]).collect();
+        regions.sort_unstable();
+        regions.dedup();
+        regions
+    }
+}
+
+// Returns None if the exit can't be reached from the start
+pub fn critical_path(map: &Map, start: usize, exit: usize, rooms: &[Rect]) -> Option<CriticalPath> {
+    let path = a_star_search(start, exit, map);
+    if !path.success {
+        return None;
+    }
+    let steps = path.steps;
+
+    let rooms = rooms
+        .iter()
+        .enumerate()
+        .filter(|(_, r)| {
+            steps.iter().any(|idx| {
+                let Point { x, y } = map.point(*idx);
+                x >= r.x1 && x <= r.x2 && y >= r.y1 && y <= r.y2
+            })
+        })
+        .map(|(i, _)| i)
+        .collect();
+
+    // DijkstraMap leaves its starting tiles unset, so the path has to be zeroed
+    let mut distance = DijkstraMap::new(map.width, map.height, &steps, map, DIJKSTRA_DEPTH).map;
+    for idx in steps.iter() {
+        distance[*idx] = 0.0;
+    }
+
+    // Anything reachable but off the path belongs to a branch; flood fill them
+    // without crossing the path, remembering where each one joins it.
+    let mut visited = vec![false; map.tiles.len()];
+    let mut branches = Vec::new();
+    for idx in 0..map.tiles.len() {
+        if visited[idx] || distance[idx] == 0.0 || distance[idx] == f32::MAX {
+            continue;
+        }
+        let mut branch = Branch {
+            root: idx,
+            tiles: Vec::new(),
+            depth: 0.0,
+        };
+        let mut open_list = vec![idx];
+        visited[idx] = true;
+        while let Some(current) = open_list.pop() {
+            branch.tiles.push(current);
+            branch.depth = f32::max(branch.depth, distance[current]);
+            for (exit, _) in map.get_available_exits(current) {
+                if distance[exit] == 0.0 {
+                    branch.root = exit;
+                } else if !visited[exit] {
+                    visited[exit] = true;
+                    open_list.push(exit);
+                }
+            }
+        }
+        branches.push(branch);
+    }
+
+    Some(CriticalPath {
+        steps,
+        rooms,
+        distance,
+        branches,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spur_off_a_corridor_is_one_branch() {
+        // A corridor along y = 5 from x = 1 to 20, with a spur down from x = 10
+        let mut map = Map::new();
+        apply_horizontal_tunnel(1, 20, 5, &mut map);
+        apply_vertical_tunnel(6, 9, 10, &mut map);
+        let analysis = critical_path(&map, map.idx(1, 5), map.idx(20, 5), &[]).unwrap();
+
+        assert!(analysis.steps.iter().all(|idx| analysis.on_path(*idx)));
+        assert!(!analysis.on_path(map.idx(10, 6)));
+        assert_eq!(analysis.branches.len(), 1);
+        let branch = &analysis.branches[0];
+        assert_eq!(branch.tiles.len(), 4);
+        assert_eq!(branch.root, map.idx(10, 5));
+        assert_eq!(branch.depth, 4.0);
+    }
+}
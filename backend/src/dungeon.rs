@@ -0,0 +1,171 @@
+use crate::*;
+
+pub struct Level {
+    pub depth: i32,
+    pub map: Map,
+    pub up_stairs: usize,
+    pub down_stairs: Option<usize>,
+}
+
+pub struct Dungeon {
+    pub levels: Vec<Level>,
+}
+
+fn open_tiles(map: &Map) -> Vec<bool> {
+    (0..map.tiles.len()).map(|i| !map.is_wall(i)).collect()
+}
+
+// Builds a level and keeps only its largest connected area, retrying a few
+// times if that leaves nothing to stand on.
+fn build_level<F>(depth: i32, builder: &mut F, rng: &mut RandomNumberGenerator) -> Map
+where
+    F: FnMut(i32, &mut RandomNumberGenerator) -> Map,
+{
+    let mut map = builder(depth, rng);
+    map.cull_disconnected();
+    for _ in 0..10 {
+        if open_tiles(&map).iter().any(|open| *open) {
+            break;
+        }
+        map = builder(depth, rng);
+        map.cull_disconnected();
+    }
+    map
+}
+
+impl Dungeon {
+    // The builder is called with each depth in turn (starting at 1), so it can
+    // pick a different algorithm or tune its parameters as the player descends.
+    // Fails if a level still has no floor for its stairs after retrying.
+    pub fn generate<F>(
+        n_levels: i32,
+        mut builder: F,
+        rng: &mut RandomNumberGenerator,
+    ) -> Result<Self, PlacementError>
+    where
+        F: FnMut(i32, &mut RandomNumberGenerator) -> Map,
+    {
+        let mut levels: Vec<Level> = Vec::new();
+        let mut map = build_level(1, &mut builder, rng);
+        let mut up_stairs = random_placement(&map, rng)?;
+
+        for depth in 1..=n_levels {
+            if depth == n_levels {
+                levels.push(Level {
+                    depth,
+                    map,
+                    up_stairs,
+                    down_stairs: None,
+                });
+                break;
+            }
+
+            // Find somewhere open on both this level and the next, as far from
+            // the up stairs as we can manage. Retry the next level if they
+            // don't overlap at all.
+            let distance =
+                DijkstraMap::new(map.width, map.height, &[up_stairs], &map, DIJKSTRA_DEPTH).map;
+            let here = open_tiles(&map);
+            let mut next = build_level(depth + 1, &mut builder, rng);
+            let mut shared = None;
+            for _ in 0..10 {
+                let below = open_tiles(&next);
+                shared = (0..map.tiles.len())
+                    .filter(|idx| {
+                        here[*idx] && below[*idx] && *idx != up_stairs && distance[*idx] < f32::MAX
+                    })
+                    .max_by(|a, b| distance[*a].partial_cmp(&distance[*b]).unwrap());
+                if shared.is_some() {
+                    break;
+                }
+                next = build_level(depth + 1, &mut builder, rng);
+            }
+
+            // Still nothing: dig the next level out to meet the stairs
+            let down_stairs = match shared {
+                Some(idx) => idx,
+                None => {
+                    let idx = find_placement(&map, &Placement::FarthestFrom(up_stairs))
+                        .unwrap_or(up_stairs);
+                    let target = map.point(idx);
+                    let nearest = find_placement(&next, &Placement::Nearest(Anchor::At(target)))?;
+                    let mut pt = map.point(nearest);
+                    while pt != target {
+                        if pt.x != target.x {
+                            pt.x += (target.x - pt.x).signum();
+                        } else {
+                            pt.y += (target.y - pt.y).signum();
+                        }
+                        let tunnel = map.idx(pt.x, pt.y);
+                        next.tiles[tunnel] = (to_cp437('#'), RGB::named(GRAY));
+                    }
+                    idx
+                }
+            };
+
+            levels.push(Level {
+                depth,
+                map,
+                up_stairs,
+                down_stairs: Some(down_stairs),
+            });
+            map = next;
+            up_stairs = down_stairs;
+        }
+
+        for level in levels.iter_mut() {
+            level.map.tiles[level.up_stairs] = (to_cp437('<'), RGB::named(GOLD));
+            if let Some(down) = level.down_stairs {
+                level.map.tiles[down] = (to_cp437('>'), RGB::named(GOLD));
+            }
+        }
+
+        Ok(Dungeon { levels })
+    }
+
+    pub fn record(&self, frames: &mut dyn SnapshotRecorder) {
+        for level in self.levels.iter() {
+            frames.record(
+                &level.map,
+                format!(
+                    "Depth {} of {} (Left/Right to change level)",
+                    level.depth,
+                    self.levels.len()
+                ),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn no_floor_is_an_error_not_stairs_in_a_wall() {
+        let mut rng = RandomNumberGenerator::seeded(1);
+        let dungeon = Dungeon::generate(2, |_, _| Map::new(), &mut rng);
+        assert!(matches!(dungeon, Err(PlacementError::NoFloor)));
+    }
+
+    #[test]
+    fn the_last_retry_is_culled_too() {
+        // Nothing to stand on until the final attempt, which has two pockets
+        let mut calls = 0;
+        let mut builder = |_, _: &mut RandomNumberGenerator| {
+            calls += 1;
+            let mut map = Map::new();
+            if calls == 11 {
+                apply_horizontal_tunnel(2, 5, 2, &mut map);
+                apply_horizontal_tunnel(10, 20, 10, &mut map);
+            }
+            map
+        };
+        let mut rng = RandomNumberGenerator::seeded(1);
+        let map = build_level(1, &mut builder, &mut rng);
+        assert_eq!(calls, 11);
+        let regions = map.regions();
+        assert!(regions.iter().all(|r| *r == usize::MAX || *r == 0));
+        assert!(!map.is_wall(map.idx(15, 10)));
+    }
+}
@@ -0,0 +1,196 @@
+use crate::*;
+use std::fmt;
+
+pub enum Violation {
+    UnreachableTreasure(Point),
+    TrappedTreasure(Point),
+    UnreachableExit(Point),
+    TrappedExit(Point),
+}
+
+impl Violation {
+    pub fn position(&self) -> Point {
+        match self {
+            Violation::UnreachableTreasure(p)
+            | Violation::TrappedTreasure(p)
+            | Violation::UnreachableExit(p)
+            | Violation::TrappedExit(p) => *p,
+        }
+    }
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let p = self.position();
+        match self {
+            Violation::UnreachableTreasure(_) => {
+                write!(f, "Treasure at ({}, {}) is unreachable", p.x, p.y)
+            }
+            Violation::TrappedTreasure(_) => {
+                write!(
+                    f,
+                    "Treasure at ({}, {}) is only reachable over a trap",
+                    p.x, p.y
+                )
+            }
+            Violation::UnreachableExit(_) => write!(f, "Exit at ({}, {}) is unreachable", p.x, p.y),
+            Violation::TrappedExit(_) => {
+                write!(
+                    f,
+                    "Path to exit at ({}, {}) is forced over a trap",
+                    p.x, p.y
+                )
+            }
+        }
+    }
+}
+
+// Treats traps as walls, so anything it can't reach means stepping on a trap
+struct TrapAvoidingMap<'a> {
+    map: &'a Map,
+}
+
+impl BaseMap for TrapAvoidingMap<'_> {
+    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
+        self.map
+            .get_available_exits(idx)
+            .into_iter()
+            .filter(|(exit, _)| self.map.tiles[*exit].0 != to_cp437('^'))
+            .collect()
+    }
+
+    fn get_pathing_distance(&self, idx1: usize, idx2: usize) -> f32 {
+        self.map.get_pathing_distance(idx1, idx2)
+    }
+}
+
+impl Algorithm2D for TrapAvoidingMap<'_> {
+    fn dimensions(&self) -> Point {
+        self.map.dimensions()
+    }
+}
+
+enum Reach {
+    Safe,
+    Trapped,
+    Unreachable,
+}
+
+pub fn check_fairness(map: &Map, start: usize, exit: Option<usize>) -> Vec<Violation> {
+    let reachable = DijkstraMap::new(map.width, map.height, &[start], map, DIJKSTRA_DEPTH);
+    let safe = DijkstraMap::new(
+        map.width,
+        map.height,
+        &[start],
+        &TrapAvoidingMap { map },
+        DIJKSTRA_DEPTH,
+    );
+    let reach = |idx: usize| {
+        if reachable.map[idx] == f32::MAX {
+            Reach::Unreachable
+        } else if safe.map[idx] == f32::MAX {
+            Reach::Trapped
+        } else {
+            Reach::Safe
+        }
+    };
+
+    let mut violations = Vec::new();
+    for (idx, _) in map
+        .tiles
+        .iter()
+        .enumerate()
+        .filter(|(_, t)| t.0 == to_cp437('$'))
+    {
+        let pt = map.point(idx);
+        match reach(idx) {
+            Reach::Unreachable => violations.push(Violation::UnreachableTreasure(pt)),
+            Reach::Trapped => violations.push(Violation::TrappedTreasure(pt)),
+            Reach::Safe => {}
+        }
+    }
+
+    if let Some(exit) = exit {
+        let pt = map.point(exit);
+        match reach(exit) {
+            Reach::Unreachable => violations.push(Violation::UnreachableExit(pt)),
+            Reach::Trapped => violations.push(Violation::TrappedExit(pt)),
+            Reach::Safe => {}
+        }
+    }
+
+    violations
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A corridor along y = 5 from x = 1 to 10, with the start at its west end
+    fn corridor() -> (Map, usize) {
+        let mut map = Map::new();
+        for x in 1..=10 {
+            map.set(Point::new(x, 5), to_cp437('#'), RGB::named(GREEN));
+        }
+        let start = map.idx(1, 5);
+        (map, start)
+    }
+
+    #[test]
+    fn exit_behind_a_trap_is_unfair() {
+        let (mut map, start) = corridor();
+        map.set(Point::new(5, 5), to_cp437('^'), RGB::named(RED));
+        let violations = check_fairness(&map, start, Some(map.idx(9, 5)));
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(violations[0], Violation::TrappedExit(p) if p == Point::new(9, 5)));
+    }
+
+    #[test]
+    fn treasure_behind_a_trap_is_unfair() {
+        let (mut map, start) = corridor();
+        map.set(Point::new(5, 5), to_cp437('^'), RGB::named(RED));
+        map.set(Point::new(8, 5), to_cp437('$'), RGB::named(GOLD));
+        let violations = check_fairness(&map, start, None);
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(violations[0], Violation::TrappedTreasure(_)));
+    }
+
+    #[test]
+    fn a_way_around_the_trap_is_fair() {
+        let (mut map, start) = corridor();
+        for x in 4..=6 {
+            map.set(Point::new(x, 6), to_cp437('#'), RGB::named(GREEN));
+        }
+        map.set(Point::new(5, 5), to_cp437('^'), RGB::named(RED));
+        map.set(Point::new(8, 5), to_cp437('$'), RGB::named(GOLD));
+        assert!(check_fairness(&map, start, Some(map.idx(10, 5))).is_empty());
+    }
+
+    #[test]
+    fn walled_off_exit_is_unreachable() {
+        let (mut map, start) = corridor();
+        map.set(Point::new(20, 20), to_cp437('#'), RGB::named(GREEN));
+        let violations = check_fairness(&map, start, Some(map.idx(20, 20)));
+        assert_eq!(violations.len(), 1);
+        assert!(matches!(violations[0], Violation::UnreachableExit(_)));
+    }
+
+    #[test]
+    fn far_away_exit_is_still_fair() {
+        // A corridor snaking across every other row, with the exit at the far
+        // end: reachable, trap-free, and well over 1024 steps from the start
+        let mut map = Map::new();
+        let (width, height) = (map.width as i32, map.height as i32);
+        let rows: Vec<i32> = (1..height - 1).step_by(2).collect();
+        for (i, y) in rows.iter().enumerate() {
+            apply_horizontal_tunnel(1, width - 2, *y, &mut map);
+            let x = if i % 2 == 0 { width - 2 } else { 1 };
+            if i + 1 < rows.len() {
+                apply_vertical_tunnel(*y, *y + 2, x, &mut map);
+            }
+        }
+        let exit = find_placement(&map, &Placement::FarthestFrom(map.idx(1, 1))).unwrap();
+        assert_eq!(map.point(exit).y, *rows.last().unwrap());
+        assert!(check_fairness(&map, map.idx(1, 1), Some(exit)).is_empty());
+    }
+}
@@ -0,0 +1,90 @@
+use crate::*;
+
+// Everything the inspector needs that costs more than a lookup, worked out
+// once per frame rather than on every mouse move
+pub struct FrameAnalysis {
+    pub frame: usize,
+    pub start: Option<usize>,
+    pub distance: Vec<f32>,
+    pub regions: Vec<usize>,
+}
+
+impl FrameAnalysis {
+    // The start is wherever the builder put its '@', or the open tile nearest
+    // the middle when it didn't mark one
+    pub fn new(map: &Map, frame: usize) -> Self {
+        let start = map
+            .tiles
+            .iter()
+            .position(|t| t.0 == to_cp437('@'))
+            .or_else(|| find_placement(map, &Placement::Nearest(Anchor::Center)).ok());
+        let distance = match start {
+            Some(start) => {
+                // DijkstraMap leaves the start itself unset
+                let mut distance =
+                    DijkstraMap::new(map.width, map.height, &[start], map, DIJKSTRA_DEPTH).map;
+                distance[start] = 0.0;
+                distance
+            }
+            None => vec![f32::MAX; map.tiles.len()],
+        };
+        Self {
+            frame,
+            start,
+            distance,
+            regions: map.regions(),
+        }
+    }
+}
+
+// One line describing the tile under the mouse, for the bottom of the viewer
+pub fn describe_tile(
+    frames: &FrameRecorder,
+    map: &Map,
+    analysis: &FrameAnalysis,
+    pt: Point,
+) -> String {
+    let idx = map.idx(pt.x, pt.y);
+    let (glyph, color) = map.tiles[idx];
+    let kind = if map.is_wall(idx) { "Wall" } else { "Open" };
+    let distance = match analysis.distance[idx] {
+        d if d < f32::MAX => format!("{:.1}", d),
+        _ => "-".to_string(),
+    };
+    let region = match analysis.regions[idx] {
+        usize::MAX => "-".to_string(),
+        r => r.to_string(),
+    };
+    format!(
+        "({},{}) #{} {} '{}' rgb({},{},{}) dist {} region {} changed {}",
+        pt.x,
+        pt.y,
+        idx,
+        kind,
+        to_char(glyph as u8),
+        (color.r * 255.0) as u8,
+        (color.g * 255.0) as u8,
+        (color.b * 255.0) as u8,
+        distance,
+        region,
+        frames.last_changed(analysis.frame, idx),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn distance_counts_from_the_start() {
+        let mut map = Map::new();
+        apply_horizontal_tunnel(1, 10, 5, &mut map);
+        map.set(Point::new(3, 5), to_cp437('@'), RGB::named(GOLD));
+        let analysis = FrameAnalysis::new(&map, 0);
+        assert_eq!(analysis.start, Some(map.idx(3, 5)));
+        assert_eq!(analysis.distance[map.idx(3, 5)], 0.0);
+        assert_eq!(analysis.distance[map.idx(4, 5)], 1.0);
+        assert_eq!(analysis.distance[map.idx(1, 5)], 2.0);
+        assert_eq!(analysis.distance[map.idx(3, 6)], f32::MAX);
+    }
+}
@@ -0,0 +1,132 @@
+use crate::*;
+
+// The effect is meant to step this often, whatever the frame rate
+const STEP_MS: f32 = 1000.0 / 15.0;
+
+#[derive(PartialEq, Clone)]
+enum Direction {
+    FadeIn,
+    FadeOut,
+}
+
+#[derive(Clone)]
+struct Entry {
+    char: FontCharType,
+    intensity: f32,
+    direction: Direction,
+}
+
+impl Entry {
+    fn new(rng: &mut RandomNumberGenerator) -> Self {
+        Self {
+            char: rng.range(0, 255),
+            intensity: rng.range(0, 255) as f32 / 255.0,
+            direction: match rng.roll_dice(1, 2) {
+                1 => Direction::FadeIn,
+                _ => Direction::FadeOut,
+            },
+        }
+    }
+
+    fn draw(&self, x: usize, y: usize, ctx: &mut BTerm) {
+        let i = self.intensity / 2.0;
+        ctx.set(x, y, RGB::from_f32(i, i, i), RGB::named(BLACK), self.char);
+    }
+
+    fn update(&mut self, rng: &mut RandomNumberGenerator) {
+        const STEP: f32 = 0.05;
+        if self.direction == Direction::FadeIn {
+            self.intensity += STEP;
+            if self.intensity > 1.0 {
+                self.direction = Direction::FadeOut;
+            }
+        } else {
+            self.intensity -= STEP;
+            if self.intensity < 0.0 {
+                self.direction = Direction::FadeIn;
+                self.char = rng.range(0, 255);
+            }
+        }
+    }
+}
+
+#[derive(Clone)]
+struct Line {
+    entries: Vec<Entry>,
+}
+
+impl Line {
+    fn new(rng: &mut RandomNumberGenerator) -> Self {
+        let mut entries = Vec::new();
+        for _ in 0..80 {
+            entries.push(Entry::new(rng))
+        }
+        Line { entries }
+    }
+
+    fn draw(&self, y: usize, ctx: &mut BTerm) {
+        for (x, c) in self.entries.iter().enumerate() {
+            c.draw(x, y, ctx);
+        }
+    }
+
+    fn update(&mut self, rng: &mut RandomNumberGenerator) {
+        self.entries.iter_mut().for_each(|entry| entry.update(rng));
+    }
+}
+
+// Columns of characters fading in and out as they scroll down the screen
+pub struct Intro {
+    rng: RandomNumberGenerator,
+    lines: Vec<Line>,
+    timer: f32,
+}
+
+impl GameState for Intro {
+    fn tick(&mut self, ctx: &mut BTerm) {
+        ctx.cls();
+        self.lines
+            .iter()
+            .enumerate()
+            .for_each(|(y, l)| l.draw(y, ctx));
+
+        self.timer += ctx.frame_time_ms;
+        if self.timer >= STEP_MS {
+            self.timer -= STEP_MS;
+            let rng = &mut self.rng;
+            self.lines.iter_mut().for_each(|line| line.update(rng));
+
+            self.lines.insert(0, self.lines[49].clone());
+            self.lines.remove(50);
+        }
+
+        // Print logo
+        ctx.print_color_centered(
+            40,
+            RGB::named(GRAY),
+            RGB::named(BLACK),
+            "Bracket Productions",
+        );
+    }
+}
+
+impl Intro {
+    pub fn new() -> Self {
+        let mut rng = RandomNumberGenerator::new();
+        let mut lines = Vec::new();
+        for _ in 0..50 {
+            lines.push(Line::new(&mut rng));
+        }
+        Intro {
+            lines,
+            rng,
+            timer: 0.0,
+        }
+    }
+}
+
+impl Default for Intro {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -1,17 +1,89 @@
 pub use bracket_lib::prelude::*;
 
-pub const WIDTH: usize = 80;
-pub const HEIGHT: usize = 50;
+mod autotile;
+pub use autotile::*;
+mod bsp;
+pub use bsp::*;
+mod caves;
+pub use caves::*;
+mod cli;
+pub use cli::*;
+mod config;
+pub use config::*;
+mod critical_path;
+pub use critical_path::*;
+mod dungeon;
+pub use dungeon::*;
+mod fairness;
+pub use fairness::*;
+mod inspect;
+pub use inspect::*;
+mod intro;
+pub use intro::*;
+mod lock_and_key;
+pub use lock_and_key::*;
+mod maze;
+pub use maze::*;
+mod movement;
+pub use movement::*;
+mod overlay;
+pub use overlay::*;
+mod placement;
+pub use placement::*;
+mod prefab;
+pub use prefab::*;
+mod presentation;
+pub use presentation::*;
+mod recorder;
+pub use recorder::*;
+mod registry;
+pub use registry::*;
+mod room_shape;
+pub use room_shape::*;
+mod rooms;
+pub use rooms::*;
+mod spawn;
+pub use spawn::*;
+mod town;
+pub use town::*;
+mod visibility;
+pub use visibility::*;
+mod wfc;
+pub use wfc::*;
+
+// The size maps get unless --width or --height says otherwise
+pub const DEFAULT_WIDTH: usize = 80;
+pub const DEFAULT_HEIGHT: usize = 50;
+
+// Depth limit for every DijkstraMap. bracket-lib leaves anything this far or
+// farther at f32::MAX, as if it were unreachable, and a winding maze can run
+// well past any small round number.
+pub const DIJKSTRA_DEPTH: f32 = f32::MAX;
 
 #[derive(Clone)]
 pub struct Map {
+    pub width: usize,
+    pub height: usize,
     pub tiles: Vec<(FontCharType, RGB)>,
+    pub movement: MovementCosts,
+    // Drawn over this frame by the viewer, never baked into the tiles
+    pub overlays: Vec<Overlay>,
 }
 
 impl Map {
+    // A solid map of the size set on the command line
     pub fn new() -> Self {
+        let (width, height) = map_size();
+        Self::with_size(width, height)
+    }
+
+    pub fn with_size(width: usize, height: usize) -> Self {
         Map {
-            tiles: vec![(to_cp437('.'), RGB::named(DARK_GRAY)); WIDTH * HEIGHT],
+            width,
+            height,
+            tiles: vec![(to_cp437('.'), RGB::named(DARK_GRAY)); width * height],
+            movement: MovementCosts::default(),
+            overlays: Vec::new(),
         }
     }
 
@@ -27,90 +99,257 @@ impl Map {
     }
 
     pub fn set(&mut self, position: Point, glyph: FontCharType, color: RGB) {
-        let idx = (position.y as usize * WIDTH) + position.x as usize;
+        let idx = self.idx(position.x, position.y);
         self.tiles[idx] = (glyph, color);
     }
 
     pub fn in_bounds(&self, point: Point) -> bool {
-        point.x >= 0 && point.x < WIDTH as i32 && point.y >= 0 && point.y < HEIGHT as i32
+        point.x >= 0 && point.x < self.width as i32 && point.y >= 0 && point.y < self.height as i32
+    }
+
+    pub fn idx(&self, x: i32, y: i32) -> usize {
+        ((y * self.width as i32) + x) as usize
+    }
+
+    pub fn point(&self, idx: usize) -> Point {
+        Point::new(idx % self.width, idx / self.width)
     }
 
     pub fn try_idx(&self, point: Point) -> Option<usize> {
         if !self.in_bounds(point) {
             None
         } else {
-            Some(mapidx(point.x, point.y))
+            Some(self.idx(point.x, point.y))
         }
     }
-}
 
-impl BaseMap for Map {
-    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
-        let mut exits = SmallVec::new();
+    pub fn is_wall(&self, idx: usize) -> bool {
+        self.tiles[idx].0 == to_cp437('.')
+    }
 
-        let x = idx % WIDTH;
-        let y = idx / WIDTH;
+    fn exit(&self, from: Point, delta: Point) -> Option<(usize, f32)> {
+        let idx = self.try_idx(from + delta)?;
+        if self.is_wall(idx) {
+            return None;
+        }
+        let diagonal = delta.x != 0 && delta.y != 0;
+        // Squeezing between two walls, or round the corner of one, isn't allowed
+        if diagonal {
+            let open = |d: Point| self.try_idx(from + d).is_some_and(|i| !self.is_wall(i));
+            if !open(Point::new(delta.x, 0)) || !open(Point::new(0, delta.y)) {
+                return None;
+            }
+        }
+        let distance = if diagonal {
+            std::f32::consts::SQRT_2
+        } else {
+            1.0
+        };
+        Some((idx, distance * self.movement.cost(self.tiles[idx].0)))
+    }
 
-        if x > 0 && self.tiles[idx - 1].0 != to_cp437('.') {
-            exits.push((idx - 1, 1.0))
+    // Numbers each connected open area; walls get usize::MAX
+    pub fn regions(&self) -> Vec<usize> {
+        let mut region = vec![usize::MAX; self.tiles.len()];
+        let mut next_id = 0;
+        for idx in 0..self.tiles.len() {
+            if self.is_wall(idx) || region[idx] != usize::MAX {
+                continue;
+            }
+            let mut open_list = vec![idx];
+            region[idx] = next_id;
+            while let Some(current) = open_list.pop() {
+                for (exit, _) in self.get_available_exits(current) {
+                    if region[exit] == usize::MAX {
+                        region[exit] = next_id;
+                        open_list.push(exit);
+                    }
+                }
+            }
+            next_id += 1;
         }
-        if x < WIDTH - 1 && self.tiles[idx + 1].0 != to_cp437('.') {
-            exits.push((idx + 1, 1.0))
+        region
+    }
+
+    // Keeps the largest connected open area and walls over everything else
+    pub fn cull_disconnected(&mut self) {
+        let region = self.regions();
+        let mut sizes = Vec::new();
+        for r in region.iter().filter(|r| **r != usize::MAX) {
+            if *r >= sizes.len() {
+                sizes.resize(*r + 1, 0);
+            }
+            sizes[*r] += 1;
         }
-        if y > 0 && self.tiles[idx - WIDTH].0 != to_cp437('.') {
-            exits.push((idx - WIDTH, 1.0))
+
+        if let Some(largest) = (0..sizes.len()).max_by_key(|id| sizes[*id]) {
+            for (idx, r) in region.iter().enumerate() {
+                if *r != usize::MAX && *r != largest {
+                    self.tiles[idx] = (to_cp437('.'), RGB::named(DARK_GRAY));
+                }
+            }
         }
-        if y < HEIGHT - 1 && self.tiles[idx + WIDTH].0 != to_cp437('.') {
-            exits.push((idx + WIDTH, 1.0))
+    }
+}
+
+impl Default for Map {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// Orthogonal moves first, so they can be taken on their own
+const DIRECTIONS: [(i32, i32); 8] = [
+    (-1, 0),
+    (1, 0),
+    (0, -1),
+    (0, 1),
+    (-1, -1),
+    (1, -1),
+    (-1, 1),
+    (1, 1),
+];
+
+impl BaseMap for Map {
+    fn is_opaque(&self, idx: usize) -> bool {
+        self.is_wall(idx)
+    }
+
+    fn get_available_exits(&self, idx: usize) -> SmallVec<[(usize, f32); 10]> {
+        let mut exits = SmallVec::new();
+        let pt = self.point(idx);
+
+        let n_directions = if self.movement.diagonals { 8 } else { 4 };
+        for (dx, dy) in DIRECTIONS.iter().take(n_directions) {
+            if let Some(exit) = self.exit(pt, Point::new(*dx, *dy)) {
+                exits.push(exit);
+            }
         }
 
         exits
     }
 
     fn get_pathing_distance(&self, idx1: usize, idx2: usize) -> f32 {
-        DistanceAlg::Pythagoras.distance2d(
-            Point::new(idx1 % WIDTH, idx1 / WIDTH),
-            Point::new(idx2 % WIDTH, idx2 / WIDTH),
-        )
+        DistanceAlg::Pythagoras.distance2d(self.point(idx1), self.point(idx2))
     }
 }
 
 impl Algorithm2D for Map {
     fn dimensions(&self) -> Point {
-        Point::new(WIDTH, HEIGHT)
+        Point::new(self.width, self.height)
     }
 }
 
-pub fn mapidx(x: i32, y: i32) -> usize {
-    ((y * WIDTH as i32) + x) as usize
-}
-
 pub trait MapGen {
     fn setup(&mut self);
-    fn build(&mut self) -> Vec<(Map, String)>;
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder);
+
+    // Spawned entities are drawn over the final frame, without touching its tiles
+    fn entities(&self) -> Vec<Entity> {
+        Vec::new()
+    }
+
+    // Shades the final frame by what can be seen from the start
+    fn visibility(&self) -> Option<Visibility> {
+        None
+    }
 }
 
-struct State {
-    builder: Box<dyn MapGen>,
-    frames: Vec<(Map, String)>,
+// Steps through one builder's frames
+struct Viewer {
+    frames: FrameRecorder,
     current_frame: usize,
+    // The current frame, rebuilt from the recorder whenever it changes
+    shown: (usize, Map),
+    entities: Vec<Entity>,
+    show_entities: bool,
+    wall_style: Option<WallStyle>,
+    visibility: Option<Visibility>,
+    show_visibility: bool,
+    show_overlays: [bool; OVERLAY_KINDS],
+    inspected: Option<FrameAnalysis>,
+    show_changes: bool,
 }
 
-impl GameState for State {
-    fn tick(&mut self, ctx: &mut BTerm) {
+impl Viewer {
+    fn new(mut builder: Box<dyn MapGen>) -> Self {
+        let mut frames = FrameRecorder::new();
+        builder.setup();
+        builder.build(&mut frames);
+        let shown = (0, frames.frame(0));
+        Self {
+            frames,
+            current_frame: 0,
+            shown,
+            entities: builder.entities(),
+            show_entities: true,
+            wall_style: None,
+            visibility: builder.visibility(),
+            show_visibility: true,
+            show_overlays: [true; OVERLAY_KINDS],
+            inspected: None,
+            show_changes: true,
+        }
+    }
+
+    // Returns false once the user steps past the last frame
+    fn tick(&mut self, ctx: &mut BTerm) -> bool {
         ctx.cls();
 
-        let map = &self.frames[self.current_frame].0;
-        for y in 0..HEIGHT {
-            for x in 0..WIDTH {
-                let idx = (y * WIDTH) + x;
+        if self.shown.0 != self.current_frame {
+            self.shown = (self.current_frame, self.frames.frame(self.current_frame));
+        }
+        let mut map = &self.shown.1;
+        let rendered;
+        if let Some(style) = self.wall_style {
+            rendered = autotile(map, style);
+            map = &rendered;
+        }
+        let last_frame = self.current_frame == self.frames.len() - 1;
+        let visibility = match &self.visibility {
+            Some(visibility) if self.show_visibility && last_frame => Some(visibility),
+            _ => None,
+        };
+        let mut changed = vec![false; map.tiles.len()];
+        let changes = self.frames.changed_tiles(self.current_frame);
+        for idx in changes.iter() {
+            changed[*idx] = true;
+        }
+        for y in 0..map.height {
+            for x in 0..map.width {
+                let idx = (y * map.width) + x;
+                let mut glyph = map.tiles[idx].0;
+                let mut fg = map.tiles[idx].1;
+                let mut bg = RGB::from_u8(0, 0, 0);
+                for overlay in map.overlays.iter() {
+                    if self.show_overlays[overlay.kind()] {
+                        overlay.apply(idx, &mut glyph, &mut fg, &mut bg);
+                    }
+                }
+                if let Some(visibility) = visibility {
+                    if !visibility.from_start[idx] {
+                        fg = fg.lerp(RGB::named(BLACK), 0.7);
+                    }
+                    if visibility.ambush.contains(&idx) {
+                        bg = RGB::named(DARK_RED);
+                    }
+                }
+                if self.show_changes && changed[idx] {
+                    bg = RGB::named(DARK_GOLDENROD);
+                }
+                ctx.set(x, y, fg, bg, glyph)
+            }
+        }
+
+        if self.show_entities && last_frame {
+            for entity in self.entities.iter() {
                 ctx.set(
-                    x,
-                    y,
-                    map.tiles[idx].1,
+                    entity.position.x,
+                    entity.position.y,
+                    entity.color,
                     RGB::from_u8(0, 0, 0),
-                    map.tiles[idx].0,
-                )
+                    entity.glyph,
+                );
             }
         }
 
@@ -118,9 +357,35 @@ impl GameState for State {
             0,
             RGB::named(WHITE),
             RGB::named(BLACK),
-            &self.frames[self.current_frame].1,
+            self.frames.caption(self.current_frame),
+        );
+        let (console_width, console_height) = ctx.get_char_size();
+        ctx.print_color_right(
+            console_width,
+            0,
+            RGB::named(WHITE),
+            RGB::named(BLACK),
+            format!("changed tiles: {}", changes.len()),
         );
 
+        let (mouse_x, mouse_y) = ctx.mouse_pos();
+        let mouse = Point::new(mouse_x, mouse_y);
+        if map.in_bounds(mouse) {
+            let current_frame = self.current_frame;
+            if !matches!(&self.inspected, Some(a) if a.frame == current_frame) {
+                self.inspected = Some(FrameAnalysis::new(&self.shown.1, current_frame));
+            }
+            if let Some(analysis) = &self.inspected {
+                ctx.print_color(
+                    0,
+                    console_height - 1,
+                    RGB::named(WHITE),
+                    RGB::named(BLACK),
+                    describe_tile(&self.frames, &self.shown.1, analysis, mouse),
+                );
+            }
+        }
+
         let mut should_continue = true;
         if let Some(key) = ctx.key {
             if key == VirtualKeyCode::Return {
@@ -129,31 +394,135 @@ impl GameState for State {
                     should_continue = false;
                 }
             }
+            if key == VirtualKeyCode::Escape {
+                should_continue = false;
+            }
+            if key == VirtualKeyCode::Left && self.current_frame > 0 {
+                self.current_frame -= 1;
+            }
+            if key == VirtualKeyCode::Right && self.current_frame < self.frames.len() - 1 {
+                self.current_frame += 1;
+            }
+            if key == VirtualKeyCode::E {
+                self.show_entities = !self.show_entities;
+            }
+            if key == VirtualKeyCode::C {
+                self.show_changes = !self.show_changes;
+            }
+            if key == VirtualKeyCode::V {
+                self.show_visibility = !self.show_visibility;
+            }
+            // Heatmap, Path, Regions and Markers, in Overlay::kind order
+            let overlay_keys = [
+                VirtualKeyCode::H,
+                VirtualKeyCode::P,
+                VirtualKeyCode::R,
+                VirtualKeyCode::M,
+            ];
+            if let Some(kind) = overlay_keys.iter().position(|k| *k == key) {
+                self.show_overlays[kind] = !self.show_overlays[kind];
+            }
+            if key == VirtualKeyCode::W {
+                self.wall_style = match self.wall_style {
+                    None => Some(WallStyle::Single),
+                    Some(WallStyle::Single) => Some(WallStyle::Double),
+                    Some(WallStyle::Double) => None,
+                };
+            }
         }
 
-        if !should_continue {
+        should_continue
+    }
+}
+
+struct State {
+    viewer: Viewer,
+}
+
+impl GameState for State {
+    fn tick(&mut self, ctx: &mut BTerm) {
+        if !self.viewer.tick(ctx) {
             ctx.quit();
         }
     }
 }
 
+// Takes the command line options in CliOptions, so any builder can be seeded,
+// tuned, run headless or exported
 pub fn run(gen: Box<dyn MapGen>) -> BError {
-    let context = BTermBuilder::simple80x50()
-        .with_title("Map Builder")
-        .build()?;
-
-    let mut gs: State = State {
-        builder: gen,
-        frames: Vec::new(),
-        current_frame: 0,
-    };
+    let options = CliOptions::from_args();
+    if options.help {
+        println!("{}", CliOptions::usage());
+        return Ok(());
+    }
+    if options.script.is_some() || options.level.is_some() {
+        eprintln!("--script and --level only work in the launcher: cargo run -- --level <name>");
+        std::process::exit(2);
+    }
+    if let Some(Err(e)) = options.config.as_ref().map(Config::params_only) {
+        eprintln!("{}", e);
+        std::process::exit(2);
+    }
+    options.apply();
+    run_with(gen, &options)
+}
 
-    gs.builder.setup();
-    gs.frames = gs.builder.build();
+// Builds headless, or in the viewer after exporting anything asked for. The
+// options must already be applied.
+pub fn run_with(gen: Box<dyn MapGen>, options: &CliOptions) -> BError {
+    if options.headless {
+        return run_headless(gen, options);
+    }
+
+    let context = open_window("Map Builder")?;
+
+    let gs = State {
+        viewer: Viewer::new(gen),
+    };
+    if let Some(format) = options.export {
+        let frames = selected_frames(&gs.viewer.frames, options.frames);
+        let mut out = std::io::stdout().lock();
+        export_frames(&frames, format, options.autotile, &mut out)?;
+        export_entities(&gs.viewer.entities, format, &mut out)?;
+    }
 
     main_loop(context, gs)
 }
 
+// Big enough for the map, and never smaller than 80x50 so the menu and
+// captions still fit
+pub(crate) fn open_window(title: &str) -> BResult<BTerm> {
+    let (width, height) = map_size();
+    BTermBuilder::simple(
+        usize::max(width, DEFAULT_WIDTH),
+        usize::max(height, DEFAULT_HEIGHT),
+    )?
+    .with_title(title)
+    .build()
+}
+
+// Picks one item with odds in proportion to its weight. None if the weights
+// add up to less than one.
+pub fn pick_weighted<T: Copy>(
+    items: &[T],
+    weight: impl Fn(&T) -> i32,
+    rng: &mut RandomNumberGenerator,
+) -> Option<T> {
+    let total: i32 = items.iter().map(&weight).sum();
+    if total < 1 {
+        return None;
+    }
+
+    let mut roll = rng.range(0, total);
+    for item in items {
+        if roll < weight(item) {
+            return Some(*item);
+        }
+        roll -= weight(item);
+    }
+    None
+}
+
 pub fn iteration_color(iter: usize) -> RGB {
     match iter {
         0 => RGB::named(WHITE),
@@ -0,0 +1,352 @@
+use crate::*;
+
+// A door fills the corridor from wall to wall, so it can be more than one tile
+// wide where corridors run side by side. Where every way on goes through a
+// room, the door is one tile in each of the room's entrances instead.
+#[derive(Clone)]
+pub struct Lock {
+    pub door: Vec<usize>,
+    pub key: usize,
+}
+
+// Wider than this and it's a room, not a corridor
+const MAX_DOOR_WIDTH: usize = 3;
+// More than this and the room is more doorway than wall
+const MAX_ENTRANCES: usize = 4;
+
+fn reachable(map: &Map, start: usize, blocked: &[usize]) -> Vec<bool> {
+    let mut seen = vec![false; map.tiles.len()];
+    if blocked.contains(&start) {
+        return seen;
+    }
+    let mut open_list = vec![start];
+    seen[start] = true;
+    while let Some(current) = open_list.pop() {
+        for (exit, _) in map.get_available_exits(current) {
+            if !seen[exit] && !blocked.contains(&exit) {
+                seen[exit] = true;
+                open_list.push(exit);
+            }
+        }
+    }
+    seen
+}
+
+// Walks the level picking up every key it can reach, opening doors as it goes.
+// Returns the order keys were collected in, or None if the exit is never reached.
+pub fn solve_locks(map: &Map, start: usize, exit: usize, locks: &[Lock]) -> Option<Vec<usize>> {
+    let mut held = vec![false; locks.len()];
+    let mut order = Vec::new();
+    loop {
+        let closed: Vec<usize> = locks
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !held[*i])
+            .flat_map(|(_, l)| l.door.iter().copied())
+            .collect();
+        let seen = reachable(map, start, &closed);
+        if seen[exit] {
+            return Some(order);
+        }
+
+        let mut found = false;
+        for (i, lock) in locks.iter().enumerate() {
+            if !held[i] && seen[lock.key] {
+                held[i] = true;
+                order.push(i);
+                found = true;
+            }
+        }
+        if !found {
+            return None;
+        }
+    }
+}
+
+fn in_room(map: &Map, idx: usize, room: &Rect) -> bool {
+    let Point { x, y } = map.point(idx);
+    x >= room.x1 && x <= room.x2 && y >= room.y1 && y <= room.y2
+}
+
+// The corridor tiles straight across the path at this step, if it's a straight
+// stretch of corridor, and they cut the start off from the exit when shut
+fn door_across(
+    map: &Map,
+    steps: &[usize],
+    step: usize,
+    start: usize,
+    exit: usize,
+    rooms: &[Rect],
+) -> Option<Vec<usize>> {
+    let (prev, here, next) = (
+        map.point(steps[step - 1]),
+        map.point(steps[step]),
+        map.point(steps[step + 1]),
+    );
+    if here - prev != next - here || (here.x != prev.x && here.y != prev.y) {
+        return None;
+    }
+    let across = if here.x == prev.x {
+        Point::new(1, 0)
+    } else {
+        Point::new(0, 1)
+    };
+    let corridor = |pt: Point| {
+        map.try_idx(pt).filter(|idx| {
+            map.tiles[*idx].0 == to_cp437('#') && !rooms.iter().any(|r| in_room(map, *idx, r))
+        })
+    };
+
+    let mut door = vec![corridor(here)?];
+    for side in [across, Point::zero() - across] {
+        let mut pt = here + side;
+        while let Some(idx) = corridor(pt) {
+            door.push(idx);
+            pt += side;
+        }
+        // Open floor that isn't corridor means this is the mouth of a room
+        if map.try_idx(pt).is_some_and(|idx| !map.is_wall(idx)) {
+            return None;
+        }
+    }
+    if door.len() > MAX_DOOR_WIDTH || reachable(map, start, &door)[exit] {
+        return None;
+    }
+    Some(door)
+}
+
+// Every open tile just outside the room, if shutting them all cuts the start
+// off from the exit
+fn room_doors(map: &Map, room: &Rect, start: usize, exit: usize) -> Option<Vec<usize>> {
+    let outside = (room.x1..=room.x2)
+        .flat_map(|x| [Point::new(x, room.y1 - 1), Point::new(x, room.y2 + 1)])
+        .chain(
+            (room.y1..=room.y2)
+                .flat_map(|y| [Point::new(room.x1 - 1, y), Point::new(room.x2 + 1, y)]),
+        );
+    let door: Vec<usize> = outside
+        .filter_map(|pt| map.try_idx(pt))
+        .filter(|idx| !map.is_wall(*idx))
+        .collect();
+    if door.is_empty()
+        || door.len() > MAX_ENTRANCES
+        || door.contains(&start)
+        || reachable(map, start, &door)[exit]
+    {
+        return None;
+    }
+    Some(door)
+}
+
+// A door at this step of the path: across the corridor, or round the room
+// it's just walked into
+fn door_at(
+    map: &Map,
+    steps: &[usize],
+    step: usize,
+    start: usize,
+    exit: usize,
+    rooms: &[Rect],
+) -> Option<Vec<usize>> {
+    match rooms.iter().find(|r| in_room(map, steps[step], r)) {
+        Some(room) if !in_room(map, steps[step - 1], room) && !in_room(map, start, room) => {
+            room_doors(map, room, start, exit)
+        }
+        Some(_) => None,
+        None => door_across(map, steps, step, start, exit, rooms),
+    }
+}
+
+// Places up to n_locks doors on the critical path, each with its key in a side
+// room that can be reached before that door. Doors are spread along the path,
+// falling back to anywhere after the last one. Every lock is checked with
+// solve_locks before it is kept.
+pub fn place_locks(
+    map: &mut Map,
+    start: usize,
+    exit: usize,
+    rooms: &[Rect],
+    n_locks: usize,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<Lock> {
+    let mut locks: Vec<Lock> = Vec::new();
+    let analysis = match critical_path(map, start, exit, rooms) {
+        Some(analysis) => analysis,
+        None => return locks,
+    };
+    let steps = &analysis.steps;
+
+    let mut last_door = 0;
+    for n in 0..n_locks {
+        // Steps with one either side, after the last door
+        let (first, last) = (last_door + 1, steps.len().saturating_sub(1));
+        if first >= last {
+            break;
+        }
+        let target = usize::clamp(steps.len() * (n + 1) / (n_locks + 1), first, last);
+
+        // Doors go where the exit can't be reached without them, trying from
+        // the target step to the end of the path, then back before it
+        let door = (target..last)
+            .chain(first..target)
+            .find_map(|step| door_at(map, steps, step, start, exit, rooms).map(|d| (step, d)));
+        let (step, door) = match door {
+            Some(door) => door,
+            None => break,
+        };
+
+        // Keys go in side rooms that are open with this door shut; earlier
+        // doors are already unlocked by the time we get here
+        let before = reachable(map, start, &door);
+        let taken: Vec<usize> = locks.iter().map(|l| l.key).collect();
+        let candidates: Vec<usize> = rooms
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !analysis.rooms.contains(i))
+            .filter_map(|(_, r)| {
+                let center = r.center();
+                (0..map.tiles.len())
+                    .filter(|idx| {
+                        in_room(map, *idx, r)
+                            && before[*idx]
+                            && *idx != start
+                            && map.tiles[*idx].0 == to_cp437('#')
+                            && !taken.contains(idx)
+                    })
+                    .min_by_key(|idx| {
+                        let pt = map.point(*idx);
+                        (pt.x - center.x).abs() + (pt.y - center.y).abs()
+                    })
+            })
+            .collect();
+        let key = match rng.random_slice_entry(&candidates) {
+            Some(key) => *key,
+            None => continue,
+        };
+
+        locks.push(Lock { door, key });
+        if solve_locks(map, start, exit, &locks).is_some() {
+            last_door = step;
+        } else {
+            locks.pop();
+        }
+    }
+
+    for (i, lock) in locks.iter().enumerate() {
+        let color = iteration_color(i + 1);
+        for door in lock.door.iter() {
+            map.tiles[*door] = (to_cp437('+'), color);
+        }
+        map.tiles[lock.key] = (to_cp437('k'), color);
+    }
+    locks
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A corridor along y = 5 from x = 1 to 20
+    fn corridor() -> Map {
+        let mut map = Map::new();
+        apply_horizontal_tunnel(1, 20, 5, &mut map);
+        map
+    }
+
+    #[test]
+    fn keys_are_collected_before_their_doors() {
+        let map = corridor();
+        let locks = [
+            Lock {
+                door: vec![map.idx(15, 5)],
+                key: map.idx(12, 5),
+            },
+            Lock {
+                door: vec![map.idx(10, 5)],
+                key: map.idx(3, 5),
+            },
+        ];
+        let order = solve_locks(&map, map.idx(1, 5), map.idx(20, 5), &locks);
+        assert_eq!(order, Some(vec![1, 0]));
+    }
+
+    #[test]
+    fn key_behind_its_own_door_is_unsolvable() {
+        let map = corridor();
+        let locks = [Lock {
+            door: vec![map.idx(10, 5)],
+            key: map.idx(15, 5),
+        }];
+        assert_eq!(
+            solve_locks(&map, map.idx(1, 5), map.idx(20, 5), &locks),
+            None
+        );
+    }
+
+    #[test]
+    fn placed_locks_can_be_solved() {
+        // Start and exit rooms joined by a corridor, with a side room off it
+        let mut map = Map::new();
+        let rooms = [
+            Rect::with_size(2, 10, 3, 3),
+            Rect::with_size(20, 2, 3, 3),
+            Rect::with_size(60, 10, 3, 3),
+        ];
+        for room in rooms.iter() {
+            room.for_each(|p| map.set(p, to_cp437('#'), RGB::named(GREEN)));
+        }
+        apply_horizontal_tunnel(5, 60, 11, &mut map);
+        apply_vertical_tunnel(5, 11, 21, &mut map);
+
+        let (start, exit) = (map.idx(3, 11), map.idx(61, 11));
+        let mut rng = RandomNumberGenerator::seeded(1);
+        let locks = place_locks(&mut map, start, exit, &rooms, 1, &mut rng);
+        assert_eq!(locks.len(), 1);
+        assert!(in_room(&map, locks[0].key, &rooms[1]));
+        assert_eq!(map.tiles[locks[0].door[0]].0, to_cp437('+'));
+        assert_eq!(solve_locks(&map, start, exit, &locks), Some(vec![0]));
+    }
+
+    // Start, side and exit rooms, with the way to the exit two tiles wide
+    fn rooms_and_corridors() -> (Map, [Rect; 3]) {
+        let mut map = Map::new();
+        let rooms = [
+            Rect::with_size(2, 10, 3, 3),
+            Rect::with_size(20, 2, 3, 3),
+            Rect::with_size(60, 10, 3, 3),
+        ];
+        for room in rooms.iter() {
+            room.for_each(|p| map.set(p, to_cp437('#'), RGB::named(GREEN)));
+        }
+        apply_horizontal_tunnel(5, 60, 11, &mut map);
+        apply_vertical_tunnel(5, 11, 21, &mut map);
+        (map, rooms)
+    }
+
+    #[test]
+    fn doubled_corridor_gets_a_wide_door() {
+        let (mut map, rooms) = rooms_and_corridors();
+        apply_horizontal_tunnel(30, 59, 12, &mut map);
+        let (start, exit) = (map.idx(3, 11), map.idx(61, 11));
+        let mut rng = RandomNumberGenerator::seeded(1);
+        let locks = place_locks(&mut map, start, exit, &rooms, 1, &mut rng);
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks[0].door.len(), 2);
+        assert_eq!(solve_locks(&map, start, exit, &locks), Some(vec![0]));
+    }
+
+    #[test]
+    fn looped_corridors_lock_the_exit_room() {
+        // A second corridor into the exit room, so no one corridor is a way in
+        let (mut map, rooms) = rooms_and_corridors();
+        apply_vertical_tunnel(11, 20, 30, &mut map);
+        apply_horizontal_tunnel(30, 61, 20, &mut map);
+        apply_vertical_tunnel(12, 20, 61, &mut map);
+        let (start, exit) = (map.idx(3, 11), map.idx(61, 11));
+        let mut rng = RandomNumberGenerator::seeded(1);
+        let locks = place_locks(&mut map, start, exit, &rooms, 1, &mut rng);
+        assert_eq!(locks.len(), 1);
+        assert_eq!(locks[0].door.len(), 2);
+        assert_eq!(solve_locks(&map, start, exit, &locks), Some(vec![0]));
+    }
+}
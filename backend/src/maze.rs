@@ -0,0 +1,478 @@
+use crate::*;
+use serde::Deserialize;
+
+#[derive(Clone, Copy, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MazeAlgorithm {
+    RecursiveBacktracker,
+    Prim,
+    Kruskal,
+    Eller,
+}
+
+// Carves a maze on a grid of corridor_width square cells, with one tile of wall
+// between them. braid is the chance of knocking each dead end through to a
+// neighbor, turning a perfect maze into one with loops.
+pub struct Maze {
+    pub algorithm: MazeAlgorithm,
+    pub corridor_width: usize,
+    pub braid: f32,
+    pub floor: RGB,
+    // Push a frame every this many passages carved
+    pub frame_every: Option<usize>,
+}
+
+struct Grid {
+    cols: usize,
+    rows: usize,
+    cell_size: usize,
+    usable: Vec<bool>,
+}
+
+impl Grid {
+    fn origin(&self, cell: usize) -> Point {
+        let step = (self.cell_size + 1) as i32;
+        Point::new(
+            1 + (cell % self.cols) as i32 * step,
+            1 + (cell / self.cols) as i32 * step,
+        )
+    }
+
+    fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let (x, y) = (cell % self.cols, cell / self.cols);
+        let mut neighbors = Vec::new();
+        if x > 0 {
+            neighbors.push(cell - 1);
+        }
+        if x < self.cols - 1 {
+            neighbors.push(cell + 1);
+        }
+        if y > 0 {
+            neighbors.push(cell - self.cols);
+        }
+        if y < self.rows - 1 {
+            neighbors.push(cell + self.cols);
+        }
+        neighbors.retain(|n| self.usable[*n]);
+        neighbors
+    }
+
+    // Opens both cells and the wall between them
+    fn carve(&self, map: &mut Map, a: usize, b: usize, floor: RGB) {
+        let size = self.cell_size as i32;
+        let (pa, pb) = (self.origin(a), self.origin(b));
+        let top_left = Point::new(i32::min(pa.x, pb.x), i32::min(pa.y, pb.y));
+        let bottom_right = Point::new(i32::max(pa.x, pb.x), i32::max(pa.y, pb.y));
+        for y in top_left.y..bottom_right.y + size {
+            for x in top_left.x..bottom_right.x + size {
+                map.set(Point::new(x, y), to_cp437('#'), floor);
+            }
+        }
+    }
+}
+
+fn shuffle<T>(items: &mut [T], rng: &mut RandomNumberGenerator) {
+    for i in (1..items.len()).rev() {
+        items.swap(i, rng.range(0, i + 1));
+    }
+}
+
+fn find_set(parent: &mut [usize], cell: usize) -> usize {
+    let mut root = cell;
+    while parent[root] != root {
+        root = parent[root];
+    }
+    parent[cell] = root;
+    root
+}
+
+impl Maze {
+    pub fn new(algorithm: MazeAlgorithm) -> Self {
+        Self {
+            algorithm,
+            corridor_width: 1,
+            braid: 0.0,
+            floor: RGB::named(CYAN),
+            frame_every: None,
+        }
+    }
+
+    pub fn build(&self, rng: &mut RandomNumberGenerator, frames: &mut dyn SnapshotRecorder) -> Map {
+        let mut map = Map::new();
+        self.fill(&mut map, rng, frames);
+        map
+    }
+
+    // Carves the maze through solid rock only, leaving a wall around anything
+    // already open, then knocks doorways through until everything is joined.
+    pub fn fill(
+        &self,
+        map: &mut Map,
+        rng: &mut RandomNumberGenerator,
+        frames: &mut dyn SnapshotRecorder,
+    ) {
+        let cell_size = usize::max(1, self.corridor_width);
+        let cols = (map.width - 1) / (cell_size + 1);
+        let rows = (map.height - 1) / (cell_size + 1);
+        let mut grid = Grid {
+            cols,
+            rows,
+            cell_size,
+            usable: Vec::new(),
+        };
+        grid.usable = (0..cols * rows)
+            .map(|cell| {
+                let origin = grid.origin(cell);
+                let size = cell_size as i32;
+                (origin.y - 1..=origin.y + size).all(|y| {
+                    (origin.x - 1..=origin.x + size).all(|x| {
+                        map.try_idx(Point::new(x, y))
+                            .is_some_and(|idx| map.is_wall(idx))
+                    })
+                })
+            })
+            .collect();
+
+        let mut links = match self.algorithm {
+            MazeAlgorithm::RecursiveBacktracker => backtracker(&grid, rng),
+            MazeAlgorithm::Prim => prim(&grid, rng),
+            MazeAlgorithm::Kruskal => kruskal(&grid, rng),
+            MazeAlgorithm::Eller => eller(&grid, rng),
+        };
+        self.add_braids(&grid, &mut links, rng);
+
+        for (i, (a, b)) in links.iter().enumerate() {
+            grid.carve(map, *a, *b, self.floor);
+            if let Some(every) = self.frame_every {
+                if (i + 1) % every == 0 {
+                    frames.record(map, format!("Carved {} Passages", i + 1));
+                }
+            }
+        }
+
+        join_regions(map, self.floor, rng, frames);
+    }
+
+    fn add_braids(
+        &self,
+        grid: &Grid,
+        links: &mut Vec<(usize, usize)>,
+        rng: &mut RandomNumberGenerator,
+    ) {
+        if self.braid <= 0.0 {
+            return;
+        }
+        let mut adjacent = vec![Vec::new(); grid.cols * grid.rows];
+        for (a, b) in links.iter() {
+            adjacent[*a].push(*b);
+            adjacent[*b].push(*a);
+        }
+
+        let mut dead_ends: Vec<usize> = (0..adjacent.len())
+            .filter(|c| adjacent[*c].len() == 1)
+            .collect();
+        shuffle(&mut dead_ends, rng);
+        for cell in dead_ends {
+            if adjacent[cell].len() != 1 || rng.range(0.0, 1.0) >= self.braid {
+                continue;
+            }
+            // Joining two dead ends removes both at once
+            let mut candidates: Vec<usize> = grid
+                .neighbors(cell)
+                .into_iter()
+                .filter(|n| !adjacent[cell].contains(n))
+                .collect();
+            if candidates.iter().any(|n| adjacent[*n].len() == 1) {
+                candidates.retain(|n| adjacent[*n].len() == 1);
+            }
+            if let Some(other) = rng.random_slice_entry(&candidates) {
+                let other = *other;
+                adjacent[cell].push(other);
+                adjacent[other].push(cell);
+                links.push((cell, other));
+            }
+        }
+    }
+}
+
+// Usable cells that can't be reached from each other get their own maze, so
+// these all return a forest rather than a single tree.
+fn backtracker(grid: &Grid, rng: &mut RandomNumberGenerator) -> Vec<(usize, usize)> {
+    let mut links = Vec::new();
+    let mut visited = vec![false; grid.cols * grid.rows];
+    let mut starts: Vec<usize> = (0..visited.len()).filter(|c| grid.usable[*c]).collect();
+    shuffle(&mut starts, rng);
+    for start in starts {
+        if visited[start] {
+            continue;
+        }
+        visited[start] = true;
+        let mut stack = vec![start];
+        while let Some(current) = stack.last() {
+            let current = *current;
+            let unvisited: Vec<usize> = grid
+                .neighbors(current)
+                .into_iter()
+                .filter(|n| !visited[*n])
+                .collect();
+            match rng.random_slice_entry(&unvisited) {
+                Some(next) => {
+                    visited[*next] = true;
+                    links.push((current, *next));
+                    stack.push(*next);
+                }
+                None => {
+                    stack.pop();
+                }
+            }
+        }
+    }
+    links
+}
+
+fn prim(grid: &Grid, rng: &mut RandomNumberGenerator) -> Vec<(usize, usize)> {
+    let mut links = Vec::new();
+    let mut in_maze = vec![false; grid.cols * grid.rows];
+    let mut in_frontier = vec![false; grid.cols * grid.rows];
+    let mut starts: Vec<usize> = (0..in_maze.len()).filter(|c| grid.usable[*c]).collect();
+    shuffle(&mut starts, rng);
+    for start in starts {
+        if in_maze[start] {
+            continue;
+        }
+        let mut frontier = vec![start];
+        in_frontier[start] = true;
+        while !frontier.is_empty() {
+            let cell = frontier.swap_remove(rng.range(0, frontier.len()));
+            let joined: Vec<usize> = grid
+                .neighbors(cell)
+                .into_iter()
+                .filter(|n| in_maze[*n])
+                .collect();
+            if let Some(other) = rng.random_slice_entry(&joined) {
+                links.push((*other, cell));
+            }
+            in_maze[cell] = true;
+            for n in grid.neighbors(cell) {
+                if !in_maze[n] && !in_frontier[n] {
+                    in_frontier[n] = true;
+                    frontier.push(n);
+                }
+            }
+        }
+    }
+    links
+}
+
+fn kruskal(grid: &Grid, rng: &mut RandomNumberGenerator) -> Vec<(usize, usize)> {
+    let mut links = Vec::new();
+    let mut edges = Vec::new();
+    for cell in (0..grid.cols * grid.rows).filter(|c| grid.usable[*c]) {
+        for n in grid.neighbors(cell) {
+            if n > cell {
+                edges.push((cell, n));
+            }
+        }
+    }
+    shuffle(&mut edges, rng);
+
+    let mut parent: Vec<usize> = (0..grid.cols * grid.rows).collect();
+    for (a, b) in edges {
+        let (set_a, set_b) = (find_set(&mut parent, a), find_set(&mut parent, b));
+        if set_a != set_b {
+            parent[set_b] = set_a;
+            links.push((a, b));
+        }
+    }
+    links
+}
+
+// Works a row at a time: randomly merge neighbors in the row, then drop at
+// least one passage down from every set. The last row merges everything left.
+fn eller(grid: &Grid, rng: &mut RandomNumberGenerator) -> Vec<(usize, usize)> {
+    let mut links = Vec::new();
+    let mut sets = vec![usize::MAX; grid.cols];
+    let mut next_set = 0;
+    for y in 0..grid.rows {
+        let row = y * grid.cols;
+        for (x, set) in sets.iter_mut().enumerate() {
+            if !grid.usable[row + x] {
+                *set = usize::MAX;
+            } else if *set == usize::MAX {
+                *set = next_set;
+                next_set += 1;
+            }
+        }
+
+        let last = y == grid.rows - 1;
+        for x in 0..grid.cols - 1 {
+            let (a, b) = (sets[x], sets[x + 1]);
+            if a == usize::MAX || b == usize::MAX || a == b {
+                continue;
+            }
+            if last || rng.range(0, 2) == 0 {
+                links.push((row + x, row + x + 1));
+                sets.iter_mut().filter(|s| **s == b).for_each(|s| *s = a);
+            }
+        }
+        if last {
+            break;
+        }
+
+        let mut below = vec![usize::MAX; grid.cols];
+        let mut ids: Vec<usize> = sets.iter().filter(|s| **s != usize::MAX).copied().collect();
+        ids.sort_unstable();
+        ids.dedup();
+        for id in ids {
+            let mut members: Vec<usize> = (0..grid.cols)
+                .filter(|x| sets[*x] == id && grid.usable[row + grid.cols + x])
+                .collect();
+            shuffle(&mut members, rng);
+            for (i, x) in members.iter().enumerate() {
+                if i == 0 || rng.range(0, 3) == 0 {
+                    links.push((row + x, row + grid.cols + x));
+                    below[*x] = id;
+                }
+            }
+        }
+        sets = below;
+    }
+    links
+}
+
+// Opens single wall tiles between areas that aren't connected yet, one at a
+// time. When no wall that thin is left, tunnels through the thicker ones
+// instead, until the map is one region or there's nowhere left to dig.
+fn join_regions(
+    map: &mut Map,
+    floor: RGB,
+    rng: &mut RandomNumberGenerator,
+    frames: &mut dyn SnapshotRecorder,
+) {
+    loop {
+        let region = map.regions();
+        if !region.iter().any(|r| *r != usize::MAX && *r > 0) {
+            return;
+        }
+
+        let doors: Vec<usize> = (0..map.tiles.len())
+            .filter(|idx| {
+                let (x, y) = (idx % map.width, idx / map.width);
+                if !map.is_wall(*idx)
+                    || x == 0
+                    || y == 0
+                    || x == map.width - 1
+                    || y == map.height - 1
+                {
+                    return false;
+                }
+                [(idx - 1, idx + 1), (idx - map.width, idx + map.width)]
+                    .iter()
+                    .any(|(a, b)| {
+                        region[*a] != usize::MAX
+                            && region[*b] != usize::MAX
+                            && region[*a] != region[*b]
+                    })
+            })
+            .collect();
+        if let Some(door) = rng.random_slice_entry(&doors) {
+            map.tiles[*door] = (to_cp437('#'), floor);
+            continue;
+        }
+
+        match tunnel_from_first_region(map, &region) {
+            Some(tunnel) => tunnel
+                .iter()
+                .for_each(|idx| map.tiles[*idx] = (to_cp437('#'), floor)),
+            None => {
+                frames.record(map, "Couldn't Join Every Region".to_string());
+                return;
+            }
+        }
+    }
+}
+
+// The shortest run of wall between region 0 and any other region, found by
+// searching outward through the walls, never digging into the map's edge.
+fn tunnel_from_first_region(map: &Map, region: &[usize]) -> Option<Vec<usize>> {
+    let (width, height) = (map.width, map.height);
+    let mut came_from = vec![usize::MAX; map.tiles.len()];
+    let mut open_list = std::collections::VecDeque::new();
+    for idx in (0..map.tiles.len()).filter(|idx| region[*idx] == 0) {
+        came_from[idx] = idx;
+        open_list.push_back(idx);
+    }
+    while let Some(current) = open_list.pop_front() {
+        let (x, y) = (current % width, current / width);
+        let mut neighbors = Vec::new();
+        if x > 0 {
+            neighbors.push(current - 1);
+        }
+        if x < width - 1 {
+            neighbors.push(current + 1);
+        }
+        if y > 0 {
+            neighbors.push(current - width);
+        }
+        if y < height - 1 {
+            neighbors.push(current + width);
+        }
+        for next in neighbors {
+            if came_from[next] != usize::MAX {
+                continue;
+            }
+            if region[next] != usize::MAX {
+                let mut tunnel = Vec::new();
+                let mut at = current;
+                while region[at] == usize::MAX {
+                    tunnel.push(at);
+                    at = came_from[at];
+                }
+                return Some(tunnel);
+            }
+            let (nx, ny) = (next % width, next / width);
+            if nx > 0 && ny > 0 && nx < width - 1 && ny < height - 1 {
+                came_from[next] = current;
+                open_list.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open_box(map: &mut Map, x: i32, y: i32) {
+        Rect::with_size(x, y, 4, 4).for_each(|p| map.set(p, to_cp437('#'), RGB::named(GREEN)));
+    }
+
+    #[test]
+    fn thick_walls_are_tunneled_through() {
+        let mut map = Map::new();
+        open_box(&mut map, 5, 5);
+        open_box(&mut map, 20, 5);
+        open_box(&mut map, 40, 30);
+        let mut rng = RandomNumberGenerator::seeded(1);
+        join_regions(&mut map, RGB::named(CYAN), &mut rng, &mut NullRecorder);
+        assert!(map.regions().iter().all(|r| *r == usize::MAX || *r == 0));
+    }
+
+    #[test]
+    fn mazes_come_out_connected() {
+        let mut rng = RandomNumberGenerator::seeded(1);
+        for algorithm in [
+            MazeAlgorithm::RecursiveBacktracker,
+            MazeAlgorithm::Prim,
+            MazeAlgorithm::Kruskal,
+            MazeAlgorithm::Eller,
+        ] {
+            let mut map = Map::new();
+            // Something already open, so the maze has to carve around it
+            open_box(&mut map, 30, 20);
+            Maze::new(algorithm).fill(&mut map, &mut rng, &mut NullRecorder);
+            assert!(map.regions().iter().all(|r| *r == usize::MAX || *r == 0));
+        }
+    }
+}
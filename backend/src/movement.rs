@@ -0,0 +1,93 @@
+use crate::*;
+
+#[derive(Clone, Default, PartialEq)]
+pub struct MovementCosts {
+    pub diagonals: bool,
+    pub tile_costs: Vec<(FontCharType, f32)>,
+}
+
+// Moves are orthogonal unless diagonals is set. A diagonal step costs sqrt(2)
+// and can't cut the corner of a wall.
+impl MovementCosts {
+    // Costs for water, rubble, doors and traps; still orthogonal moves only
+    pub fn dungeon() -> Self {
+        Self {
+            diagonals: false,
+            tile_costs: vec![
+                (to_cp437('~'), 3.0),
+                (to_cp437(':'), 2.0),
+                (to_cp437('+'), 1.5),
+                (to_cp437('^'), 20.0),
+            ],
+        }
+    }
+
+    // Cost of entering a tile; anything not listed costs 1.0
+    pub fn cost(&self, glyph: FontCharType) -> f32 {
+        self.tile_costs
+            .iter()
+            .find(|(g, _)| *g == glyph)
+            .map_or(1.0, |(_, cost)| *cost)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn open(map: &mut Map, x: i32, y: i32) {
+        map.set(Point::new(x, y), to_cp437('#'), RGB::named(GREEN));
+    }
+
+    fn cost_to(map: &Map, from: (i32, i32), to: (i32, i32)) -> Option<f32> {
+        map.get_available_exits(map.idx(from.0, from.1))
+            .into_iter()
+            .find(|(idx, _)| *idx == map.idx(to.0, to.1))
+            .map(|(_, cost)| cost)
+    }
+
+    #[test]
+    fn diagonals_only_when_asked_for() {
+        let mut map = Map::new();
+        for (x, y) in [(5, 5), (6, 5), (5, 6), (6, 6)] {
+            open(&mut map, x, y);
+        }
+        assert_eq!(cost_to(&map, (5, 5), (6, 6)), None);
+        map.movement.diagonals = true;
+        assert_eq!(
+            cost_to(&map, (5, 5), (6, 6)),
+            Some(std::f32::consts::SQRT_2)
+        );
+    }
+
+    #[test]
+    fn diagonals_cant_squeeze_between_walls() {
+        let mut map = Map::new();
+        map.movement.diagonals = true;
+        open(&mut map, 5, 5);
+        open(&mut map, 6, 6);
+        assert_eq!(cost_to(&map, (5, 5), (6, 6)), None);
+        assert_ne!(map.regions()[map.idx(5, 5)], map.regions()[map.idx(6, 6)]);
+    }
+
+    #[test]
+    fn diagonals_cant_cut_a_corner() {
+        let mut map = Map::new();
+        map.movement.diagonals = true;
+        open(&mut map, 5, 5);
+        open(&mut map, 6, 5);
+        open(&mut map, 6, 6);
+        assert_eq!(cost_to(&map, (5, 5), (6, 6)), None);
+        assert_eq!(cost_to(&map, (6, 5), (6, 6)), Some(1.0));
+    }
+
+    #[test]
+    fn tiles_cost_what_they_are_listed_at() {
+        let mut map = Map::new();
+        map.movement = MovementCosts::dungeon();
+        open(&mut map, 5, 5);
+        map.set(Point::new(6, 5), to_cp437('~'), RGB::named(BLUE));
+        assert_eq!(cost_to(&map, (5, 5), (6, 5)), Some(3.0));
+        assert_eq!(cost_to(&map, (6, 5), (5, 5)), Some(1.0));
+    }
+}
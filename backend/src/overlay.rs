@@ -0,0 +1,96 @@
+use crate::*;
+
+// Display-only layers that travel with a frame's map. The viewer draws them
+// over the tiles without changing them, and each kind can be toggled.
+#[derive(Clone, PartialEq)]
+pub enum Overlay {
+    // Unreachable tiles (f32::MAX, as DijkstraMap uses) are left alone
+    Heatmap { values: Vec<f32>, max: f32 },
+    Path(Vec<(usize, FontCharType)>),
+    // One id per tile; usize::MAX for none
+    Regions(Vec<usize>),
+    Markers(Vec<(usize, FontCharType, RGB)>),
+}
+
+pub const OVERLAY_KINDS: usize = 4;
+
+impl Overlay {
+    pub fn heatmap(values: &[f32]) -> Self {
+        let max = values
+            .iter()
+            .filter(|v| **v < f32::MAX)
+            .fold(0.0, |a: f32, b| a.max(*b));
+        Overlay::Heatmap {
+            values: values.to_vec(),
+            max,
+        }
+    }
+
+    // Joins the steps up with line-drawing glyphs, so the route reads as a line.
+    // The two ends are left out, so whatever marks the start and goal shows.
+    pub fn path(map: &Map, steps: &[usize]) -> Self {
+        let glyphs = WallStyle::Single.glyphs();
+        let to_point = |idx: usize| map.point(idx);
+        let line = steps
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| *i > 0 && *i + 1 < steps.len())
+            .map(|(i, idx)| {
+                let here = to_point(*idx);
+                let mut mask = 0;
+                for n in [to_point(steps[i - 1]), to_point(steps[i + 1])] {
+                    let (dx, dy) = (n.x - here.x, n.y - here.y);
+                    match (dx, dy) {
+                        (0, -1) => mask |= 1,
+                        (0, 1) => mask |= 2,
+                        (-1, 0) => mask |= 4,
+                        (1, 0) => mask |= 8,
+                        _ if dx * dy < 0 => return (*idx, to_cp437('/')),
+                        _ => return (*idx, to_cp437('\\')),
+                    }
+                }
+                (*idx, to_cp437(glyphs[mask]))
+            })
+            .collect();
+        Overlay::Path(line)
+    }
+
+    // Which toggle controls this layer
+    pub fn kind(&self) -> usize {
+        match self {
+            Overlay::Heatmap { .. } => 0,
+            Overlay::Path(_) => 1,
+            Overlay::Regions(_) => 2,
+            Overlay::Markers(_) => 3,
+        }
+    }
+
+    // Adjusts one tile's glyph and colors as the viewer is about to draw them
+    pub fn apply(&self, idx: usize, glyph: &mut FontCharType, fg: &mut RGB, bg: &mut RGB) {
+        match self {
+            Overlay::Heatmap { values, max } => {
+                if values[idx] < f32::MAX && *max > 0.0 {
+                    let t = values[idx] / max;
+                    *bg = RGB::named(NAVY).lerp(RGB::named(DARK_RED), t);
+                }
+            }
+            Overlay::Path(line) => {
+                if let Some((_, g)) = line.iter().find(|(i, _)| *i == idx) {
+                    *glyph = *g;
+                    *fg = RGB::named(YELLOW);
+                }
+            }
+            Overlay::Regions(ids) => {
+                if ids[idx] != usize::MAX {
+                    *bg = iteration_color(ids[idx] % 14).lerp(RGB::named(BLACK), 0.6);
+                }
+            }
+            Overlay::Markers(markers) => {
+                if let Some((_, g, color)) = markers.iter().find(|(i, _, _)| *i == idx) {
+                    *glyph = *g;
+                    *fg = *color;
+                }
+            }
+        }
+    }
+}
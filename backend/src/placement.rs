@@ -0,0 +1,190 @@
+use crate::*;
+use std::fmt;
+
+#[derive(Clone, Copy)]
+pub enum Anchor {
+    Left,
+    Right,
+    Top,
+    Bottom,
+    Center,
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+    At(Point),
+}
+
+impl Anchor {
+    pub fn point(&self, map: &Map) -> Point {
+        let (w, h) = (map.width as i32, map.height as i32);
+        match self {
+            Anchor::Left => Point::new(0, h / 2),
+            Anchor::Right => Point::new(w - 1, h / 2),
+            Anchor::Top => Point::new(w / 2, 0),
+            Anchor::Bottom => Point::new(w / 2, h - 1),
+            Anchor::Center => Point::new(w / 2, h / 2),
+            Anchor::TopLeft => Point::new(0, 0),
+            Anchor::TopRight => Point::new(w - 1, 0),
+            Anchor::BottomLeft => Point::new(0, h - 1),
+            Anchor::BottomRight => Point::new(w - 1, h - 1),
+            Anchor::At(pt) => *pt,
+        }
+    }
+}
+
+#[derive(Clone, Copy)]
+pub enum Placement {
+    Nearest(Anchor),
+    FarthestFrom(usize),
+    InRoom(Rect),
+}
+
+#[derive(Debug)]
+pub enum PlacementError {
+    NoFloor,
+}
+
+impl fmt::Display for PlacementError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            PlacementError::NoFloor => write!(f, "There is no open floor to place on"),
+        }
+    }
+}
+
+impl std::error::Error for PlacementError {}
+
+fn floor_tiles(map: &Map) -> Vec<usize> {
+    (0..map.tiles.len()).filter(|i| !map.is_wall(*i)).collect()
+}
+
+fn nearest(map: &Map, candidates: &[usize], target: Point) -> Option<usize> {
+    candidates
+        .iter()
+        .map(|i| {
+            (
+                *i,
+                DistanceAlg::Pythagoras.distance2d(target, map.point(*i)),
+            )
+        })
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+        .map(|(i, _)| i)
+}
+
+fn farthest(map: &Map, from: usize) -> usize {
+    let dijkstra = DijkstraMap::new(map.width, map.height, &[from], map, DIJKSTRA_DEPTH);
+    dijkstra
+        .map
+        .iter()
+        .enumerate()
+        .filter(|(_i, d)| **d < f32::MAX)
+        .max_by(|a, b| a.1.partial_cmp(b.1).unwrap())
+        .map_or(from, |(i, _)| i)
+}
+
+pub fn find_placement(map: &Map, placement: &Placement) -> Result<usize, PlacementError> {
+    let floor = floor_tiles(map);
+    if floor.is_empty() {
+        return Err(PlacementError::NoFloor);
+    }
+
+    let idx = match placement {
+        Placement::Nearest(anchor) => nearest(map, &floor, anchor.point(map)),
+        Placement::FarthestFrom(start) => Some(farthest(map, *start)),
+        Placement::InRoom(room) => {
+            let inside: Vec<usize> = floor
+                .iter()
+                .filter(|i| {
+                    let pt = map.point(**i);
+                    pt.x >= room.x1 && pt.x <= room.x2 && pt.y >= room.y1 && pt.y <= room.y2
+                })
+                .copied()
+                .collect();
+            nearest(map, &inside, room.center())
+        }
+    };
+    idx.ok_or(PlacementError::NoFloor)
+}
+
+// Any open tile at all; the only placement that needs the rng
+pub fn random_placement(
+    map: &Map,
+    rng: &mut RandomNumberGenerator,
+) -> Result<usize, PlacementError> {
+    rng.random_slice_entry(&floor_tiles(map))
+        .copied()
+        .ok_or(PlacementError::NoFloor)
+}
+
+// Two Dijkstra sweeps across the largest region: the farthest tile from
+// anywhere in it, then the farthest tile from that. On a tree (a perfect maze)
+// that's exactly the longest path; with loops it's a good guess, not a promise.
+pub fn map_diameter(map: &Map) -> Result<(usize, usize), PlacementError> {
+    let regions = map.regions();
+    let mut sizes = std::collections::HashMap::<usize, usize>::new();
+    for r in regions.iter().filter(|r| **r != usize::MAX) {
+        *sizes.entry(*r).or_insert(0) += 1;
+    }
+    let largest = sizes
+        .iter()
+        .max_by_key(|(r, size)| (**size, std::cmp::Reverse(**r)))
+        .map(|(r, _)| *r)
+        .ok_or(PlacementError::NoFloor)?;
+    let first = regions.iter().position(|r| *r == largest).unwrap();
+    let start = farthest(map, first);
+    let exit = farthest(map, start);
+    Ok((start, exit))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diameter_spans_the_largest_region() {
+        let mut map = Map::new();
+        // A scrap of floor in the corner, then a longer corridor elsewhere
+        map.set(Point::new(1, 1), to_cp437('#'), RGB::named(GREEN));
+        map.set(Point::new(2, 1), to_cp437('#'), RGB::named(GREEN));
+        for x in 10..=30 {
+            map.set(Point::new(x, 20), to_cp437('#'), RGB::named(GREEN));
+        }
+        let (start, exit) = map_diameter(&map).unwrap();
+        let mut ends = [start, exit];
+        ends.sort_unstable();
+        assert_eq!(ends, [map.idx(10, 20), map.idx(30, 20)]);
+    }
+
+    #[test]
+    fn diameter_of_a_maze_longer_than_1024_steps() {
+        // A corridor snaking across every other row, nearly 1900 steps end to end
+        let mut map = Map::new();
+        let (width, height) = (map.width as i32, map.height as i32);
+        let rows: Vec<i32> = (1..height - 1).step_by(2).collect();
+        for (i, y) in rows.iter().enumerate() {
+            apply_horizontal_tunnel(1, width - 2, *y, &mut map);
+            let x = if i % 2 == 0 { width - 2 } else { 1 };
+            if i + 1 < rows.len() {
+                apply_vertical_tunnel(*y, *y + 2, x, &mut map);
+            }
+        }
+        let last_y = *rows.last().unwrap();
+        let far_end = if rows.len() % 2 == 0 { 1 } else { width - 2 };
+
+        let (start, exit) = map_diameter(&map).unwrap();
+        let mut ends = [start, exit];
+        ends.sort_unstable();
+        assert_eq!(ends, [map.idx(1, 1), map.idx(far_end, last_y)]);
+        let farthest = find_placement(&map, &Placement::FarthestFrom(map.idx(1, 1))).unwrap();
+        assert_eq!(farthest, map.idx(far_end, last_y));
+    }
+
+    #[test]
+    fn any_open_glyph_counts_as_floor() {
+        let mut map = Map::new();
+        map.set(Point::new(5, 5), to_cp437('+'), RGB::named(YELLOW));
+        let idx = find_placement(&map, &Placement::Nearest(Anchor::Center)).unwrap();
+        assert_eq!(idx, map.idx(5, 5));
+    }
+}
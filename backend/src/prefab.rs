@@ -0,0 +1,189 @@
+use crate::*;
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum HorizontalPlacement {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum VerticalPlacement {
+    Top,
+    Center,
+    Bottom,
+}
+
+pub struct PrefabSection {
+    pub template: &'static str,
+    pub width: usize,
+    pub height: usize,
+    pub placement: (HorizontalPlacement, VerticalPlacement),
+    // Nudges the section away from where its placement puts it
+    pub offset: Point,
+}
+
+impl PrefabSection {
+    // Sections bigger than the map hang off its far side
+    pub fn origin(&self, map: &Map) -> Point {
+        let (map_w, map_h) = (map.width as i32, map.height as i32);
+        let (w, h) = (self.width as i32, self.height as i32);
+        let x = match self.placement.0 {
+            HorizontalPlacement::Left => 0,
+            HorizontalPlacement::Center => (map_w / 2) - (w / 2),
+            HorizontalPlacement::Right => map_w - w,
+        };
+        let y = match self.placement.1 {
+            VerticalPlacement::Top => 0,
+            VerticalPlacement::Center => (map_h / 2) - (h / 2),
+            VerticalPlacement::Bottom => map_h - h,
+        };
+        Point::new(x, y) + self.offset
+    }
+}
+
+pub fn template_chars(template: &str) -> Vec<char> {
+    template
+        .chars()
+        .filter(|a| *a != '\r' && *a != '\n')
+        .collect()
+}
+
+// '#' is a wall, '.' is open floor (existing floor keeps its color), '^' is a trap,
+// '$' is treasure, '_' an altar and '&' a shrine. Anything else leaves the
+// underlying map alone.
+pub fn apply_prefab(map: &mut Map, template: &str, width: usize, origin: Point, floor: RGB) {
+    for (i, c) in template_chars(template).iter().enumerate() {
+        let pt = origin + Point::new(i % width, i / width);
+        if let Some(idx) = map.try_idx(pt) {
+            match c {
+                '#' => map.tiles[idx] = (to_cp437('.'), RGB::named(DARK_GRAY)),
+                '.' if map.is_wall(idx) => map.tiles[idx] = (to_cp437('#'), floor),
+                '^' => map.tiles[idx] = (to_cp437('^'), RGB::named(RED)),
+                '$' => map.tiles[idx] = (to_cp437('$'), RGB::named(GOLD)),
+                '_' => map.tiles[idx] = (to_cp437('_'), RGB::named(WHITE)),
+                '&' => map.tiles[idx] = (to_cp437('&'), RGB::named(CYAN)),
+                _ => {}
+            }
+        }
+    }
+}
+
+pub fn apply_sectional(map: &mut Map, section: &PrefabSection, floor: RGB) {
+    apply_prefab(
+        map,
+        section.template,
+        section.width,
+        section.origin(map),
+        floor,
+    );
+    map.cull_disconnected();
+}
+
+#[derive(Clone, Copy)]
+pub struct RoomPrefab {
+    pub template: &'static str,
+    pub width: usize,
+    pub height: usize,
+    pub weight: i32,
+}
+
+impl RoomPrefab {
+    pub fn fits(&self, room: &Rect) -> bool {
+        // Rect::for_each is inclusive, so a room covers width + 1 tiles
+        self.width as i32 <= room.width() + 1 && self.height as i32 <= room.height() + 1
+    }
+}
+
+pub fn pick_room_prefab(
+    room: &Rect,
+    prefabs: &[RoomPrefab],
+    rng: &mut RandomNumberGenerator,
+) -> Option<RoomPrefab> {
+    let candidates: Vec<&RoomPrefab> = prefabs.iter().filter(|p| p.fits(room)).collect();
+    pick_weighted(&candidates, |p| p.weight, rng).copied()
+}
+
+pub fn apply_room_prefab(map: &mut Map, room: &Rect, prefab: &RoomPrefab, floor: RGB) {
+    let origin = Point::new(
+        room.x1 + (room.width() + 1 - prefab.width as i32) / 2,
+        room.y1 + (room.height() + 1 - prefab.height as i32) / 2,
+    );
+    apply_prefab(map, prefab.template, prefab.width, origin, floor);
+}
+
+// Furnishes every room with a weighted pick from the prefabs that fit inside it,
+// returning the rooms that received one.
+pub fn apply_room_prefabs(
+    map: &mut Map,
+    rooms: &[Rect],
+    prefabs: &[RoomPrefab],
+    floor: RGB,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<(Rect, RoomPrefab)> {
+    let mut furnished = Vec::new();
+    for room in rooms.iter() {
+        if let Some(prefab) = pick_room_prefab(room, prefabs, rng) {
+            apply_room_prefab(map, room, &prefab, floor);
+            furnished.push((*room, prefab));
+        }
+    }
+    furnished
+}
+
+pub const ROOM_PREFABS: &[RoomPrefab] = &[
+    RoomPrefab {
+        template: PILLARS,
+        width: 7,
+        height: 5,
+        weight: 4,
+    },
+    RoomPrefab {
+        template: ALTAR,
+        width: 5,
+        height: 3,
+        weight: 2,
+    },
+    RoomPrefab {
+        template: SHRINE,
+        width: 5,
+        height: 5,
+        weight: 1,
+    },
+    RoomPrefab {
+        template: TREASURE_ROOM,
+        width: 5,
+        height: 5,
+        weight: 1,
+    },
+];
+
+const PILLARS: &str = "
+.......
+.#.#.#.
+.......
+.#.#.#.
+.......
+";
+
+const ALTAR: &str = "
+.....
+.._..
+.....
+";
+
+const SHRINE: &str = "
+.....
+.#.#.
+..&..
+.#.#.
+.....
+";
+
+const TREASURE_ROOM: &str = "
+.....
+.#$#.
+.$$$.
+.#$#.
+.....
+";
@@ -0,0 +1,251 @@
+use crate::*;
+
+pub enum Slide {
+    Intro,
+    Title(Vec<String>),
+    // A registered chapter, by name
+    Chapter(String),
+}
+
+pub struct ScriptSlide {
+    pub slide: Slide,
+    // Seconds before moving on. Chapters step a frame each time instead, and
+    // move on after their last frame.
+    pub advance: Option<f32>,
+    pub notes: Vec<String>,
+}
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Io(std::io::Error),
+    Parse { line: usize, message: String },
+    UnknownChapter(String),
+}
+
+impl std::fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        match self {
+            ScriptError::Io(e) => write!(f, "Couldn't read the script: {}", e),
+            ScriptError::Parse { line, message } => write!(f, "Line {}: {}", line, message),
+            ScriptError::UnknownChapter(name) => write!(f, "No chapter called \"{}\"", name),
+        }
+    }
+}
+
+impl std::error::Error for ScriptError {}
+
+impl From<std::io::Error> for ScriptError {
+    fn from(e: std::io::Error) -> Self {
+        ScriptError::Io(e)
+    }
+}
+
+pub fn load_script(path: &str) -> Result<Vec<ScriptSlide>, ScriptError> {
+    parse_script(&std::fs::read_to_string(path)?)
+}
+
+// Scripts are a list of [intro], [title] and [chapter] sections, each with
+// "key = value" lines; text and notes can be given more than once to add lines.
+// Anything after a # is a comment.
+pub fn parse_script(script: &str) -> Result<Vec<ScriptSlide>, ScriptError> {
+    let mut slides = Vec::new();
+    let mut sections = Vec::new();
+    for (i, raw) in script.lines().enumerate() {
+        let line = i + 1;
+        let error = |message: &str| ScriptError::Parse {
+            line,
+            message: message.to_string(),
+        };
+        let text = raw.split('#').next().unwrap_or("").trim();
+        if text.is_empty() {
+            continue;
+        }
+
+        if text.starts_with('[') && text.ends_with(']') {
+            let slide = match &text[1..text.len() - 1] {
+                "intro" => Slide::Intro,
+                "title" => Slide::Title(Vec::new()),
+                "chapter" => Slide::Chapter(String::new()),
+                _ => return Err(error("Sections are [intro], [title] or [chapter]")),
+            };
+            slides.push(ScriptSlide {
+                slide,
+                advance: None,
+                notes: Vec::new(),
+            });
+            sections.push(line);
+            continue;
+        }
+
+        let (key, value) = match text.split_once('=') {
+            Some((key, value)) => (key.trim(), value.trim()),
+            None => return Err(error("Expected key = value")),
+        };
+        let current = match slides.last_mut() {
+            Some(current) => current,
+            None => return Err(error("Settings need a section above them")),
+        };
+        match (key, &mut current.slide) {
+            ("advance", _) => match value.parse::<f32>() {
+                Ok(seconds) if seconds > 0.0 => current.advance = Some(seconds),
+                _ => return Err(error("advance should be a number of seconds")),
+            },
+            ("notes", _) => current.notes.push(value.to_string()),
+            ("text", Slide::Title(lines)) => lines.push(value.to_string()),
+            ("name", Slide::Chapter(name)) => *name = value.to_string(),
+            _ => return Err(error(&format!("{} doesn't belong here", key))),
+        }
+    }
+
+    for (slide, line) in slides.iter().zip(sections) {
+        if matches!(&slide.slide, Slide::Chapter(name) if name.is_empty()) {
+            return Err(ScriptError::Parse {
+                line,
+                message: "Every [chapter] needs a name".to_string(),
+            });
+        }
+    }
+    Ok(slides)
+}
+
+struct Presentation {
+    registry: Registry,
+    slides: Vec<ScriptSlide>,
+    current: usize,
+    intro: Intro,
+    // The running chapter, when the current slide is one
+    viewer: Option<Viewer>,
+    elapsed: f32,
+    paused: bool,
+    show_notes: bool,
+}
+
+impl Presentation {
+    // Also prints the notes to the terminal, for a presenter with two screens
+    fn go_to(&mut self, slide: usize) {
+        self.current = slide;
+        self.elapsed = 0.0;
+        self.viewer = match &self.slides[slide].slide {
+            Slide::Chapter(name) => self.registry.find(name).map(|chapter| chapter.viewer()),
+            _ => None,
+        };
+        println!("--- Slide {} of {} ---", slide + 1, self.slides.len());
+        for note in self.slides[slide].notes.iter() {
+            println!("{}", note);
+        }
+    }
+
+    // Returns false when there are no slides left
+    fn next(&mut self) -> bool {
+        if self.current + 1 < self.slides.len() {
+            self.go_to(self.current + 1);
+            true
+        } else {
+            false
+        }
+    }
+
+    fn draw_title(lines: &[String], ctx: &mut BTerm) {
+        let height = ctx.get_char_size().1 as usize;
+        let top = height.saturating_sub(lines.len() * 2) / 2;
+        for (i, line) in lines.iter().enumerate() {
+            let color = if i == 0 {
+                RGB::named(YELLOW)
+            } else {
+                RGB::named(WHITE)
+            };
+            ctx.print_color_centered(top + i * 2, color, RGB::named(BLACK), line);
+        }
+    }
+
+    fn draw_notes(&self, ctx: &mut BTerm) {
+        let notes = &self.slides[self.current].notes;
+        let height = ctx.get_char_size().1 as usize;
+        let top = (height - 1).saturating_sub(notes.len());
+        for (i, note) in notes.iter().enumerate() {
+            ctx.print_color(0, top + i, RGB::named(CYAN), RGB::named(BLACK), note);
+        }
+    }
+}
+
+impl GameState for Presentation {
+    fn tick(&mut self, ctx: &mut BTerm) {
+        let mut should_continue = true;
+        match &self.slides[self.current].slide {
+            Slide::Intro => self.intro.tick(ctx),
+            Slide::Title(lines) => {
+                ctx.cls();
+                Self::draw_title(lines, ctx);
+            }
+            Slide::Chapter(_) => {
+                if let Some(viewer) = &mut self.viewer {
+                    if !viewer.tick(ctx) {
+                        should_continue = self.next();
+                    }
+                }
+            }
+        }
+        if self.show_notes {
+            self.draw_notes(ctx);
+        }
+
+        // Chapters step through their frames on the timer, everything else
+        // moves on to the next slide
+        self.elapsed += ctx.frame_time_ms / 1000.0;
+        if let Some(advance) = self.slides[self.current].advance {
+            if !self.paused && self.elapsed >= advance {
+                self.elapsed = 0.0;
+                match &mut self.viewer {
+                    Some(viewer) if viewer.current_frame + 1 < viewer.frames.len() => {
+                        viewer.current_frame += 1
+                    }
+                    _ => should_continue = self.next(),
+                }
+            }
+        }
+
+        match ctx.key {
+            Some(VirtualKeyCode::PageDown) => should_continue = self.next(),
+            Some(VirtualKeyCode::PageUp) if self.current > 0 => self.go_to(self.current - 1),
+            Some(VirtualKeyCode::Space) => self.paused = !self.paused,
+            Some(VirtualKeyCode::N) => self.show_notes = !self.show_notes,
+            Some(VirtualKeyCode::Escape) => should_continue = false,
+            _ => {}
+        }
+
+        if !should_continue {
+            ctx.quit();
+        }
+    }
+}
+
+// Runs the whole talk from a script: PageUp and PageDown change slide, Space
+// pauses the timers and N shows the speaker notes
+pub fn run_presentation(registry: Registry, slides: Vec<ScriptSlide>) -> BError {
+    for slide in slides.iter() {
+        if let Slide::Chapter(name) = &slide.slide {
+            if registry.find(name).is_none() {
+                return Err(ScriptError::UnknownChapter(name.clone()).into());
+            }
+        }
+    }
+    if slides.is_empty() {
+        return Ok(());
+    }
+
+    let context = open_window("Procedural Map Generation")?;
+
+    let mut gs = Presentation {
+        registry,
+        slides,
+        current: 0,
+        intro: Intro::new(),
+        viewer: None,
+        elapsed: 0.0,
+        paused: false,
+        show_notes: false,
+    };
+    gs.go_to(0);
+
+    main_loop(context, gs)
+}
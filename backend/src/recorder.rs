@@ -0,0 +1,148 @@
+use crate::*;
+
+// Builders take snapshots through this, so the same code can run with the
+// viewer watching or at full speed in a game
+pub trait SnapshotRecorder {
+    fn record(&mut self, map: &Map, caption: String);
+
+    // Lets a builder skip work that only exists to make a snapshot
+    fn is_recording(&self) -> bool {
+        true
+    }
+}
+
+// Throws every snapshot away
+pub struct NullRecorder;
+
+impl SnapshotRecorder for NullRecorder {
+    fn record(&mut self, _map: &Map, _caption: String) {}
+
+    fn is_recording(&self) -> bool {
+        false
+    }
+}
+
+// Keeps only the last snapshot, for when the finished map is all that matters
+#[derive(Default)]
+pub struct LatestRecorder {
+    pub latest: Option<(Map, String)>,
+}
+
+impl SnapshotRecorder for LatestRecorder {
+    fn record(&mut self, map: &Map, caption: String) {
+        self.latest = Some((map.clone(), caption));
+    }
+}
+
+// A whole map is kept this often, so seeking never replays more deltas than this
+const KEYFRAME_EVERY: usize = 32;
+
+// What changed since the previous frame. Movement costs and overlays rarely
+// change, so they're only stored when they do.
+struct Delta {
+    tiles: Vec<(usize, (FontCharType, RGB))>,
+    movement: Option<MovementCosts>,
+    overlays: Option<Vec<Overlay>>,
+}
+
+// Stores frames as the tiles that changed since the one before, plus the odd
+// keyframe, so a builder can snapshot every step without keeping a full map
+// for each.
+#[derive(Default)]
+pub struct FrameRecorder {
+    keyframes: Vec<Map>,
+    deltas: Vec<Delta>,
+    captions: Vec<String>,
+    // The most recently recorded map, kept up to date by applying each delta
+    latest: Map,
+}
+
+impl FrameRecorder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.captions.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.captions.is_empty()
+    }
+
+    pub fn caption(&self, frame: usize) -> &str {
+        &self.captions[frame]
+    }
+
+    // Rebuilds a frame from the keyframe before it
+    pub fn frame(&self, frame: usize) -> Map {
+        let keyframe = frame / KEYFRAME_EVERY;
+        let mut map = self.keyframes[keyframe].clone();
+        for delta in self.deltas[keyframe * KEYFRAME_EVERY + 1..=frame].iter() {
+            for (idx, tile) in delta.tiles.iter() {
+                map.tiles[*idx] = *tile;
+            }
+            if let Some(movement) = &delta.movement {
+                map.movement = movement.clone();
+            }
+            if let Some(overlays) = &delta.overlays {
+                map.overlays = overlays.clone();
+            }
+        }
+        map
+    }
+
+    // Tiles that differ from the frame before; the first frame has nothing to
+    // compare with, so nothing has changed
+    pub fn changed_tiles(&self, frame: usize) -> Vec<usize> {
+        self.deltas[frame]
+            .tiles
+            .iter()
+            .map(|(idx, _)| *idx)
+            .collect()
+    }
+
+    // The most recent frame, up to and including this one, where the tile
+    // changed
+    pub fn last_changed(&self, frame: usize, idx: usize) -> usize {
+        (1..=frame)
+            .rev()
+            .find(|f| self.deltas[*f].tiles.iter().any(|(i, _)| *i == idx))
+            .unwrap_or(0)
+    }
+}
+
+impl SnapshotRecorder for FrameRecorder {
+    fn record(&mut self, map: &Map, caption: String) {
+        let frame = self.captions.len();
+        let mut delta = Delta {
+            tiles: Vec::new(),
+            movement: None,
+            overlays: None,
+        };
+        if frame == 0 {
+            self.latest = map.clone();
+        } else {
+            for (idx, tile) in map.tiles.iter().enumerate() {
+                if self.latest.tiles[idx] != *tile {
+                    delta.tiles.push((idx, *tile));
+                    self.latest.tiles[idx] = *tile;
+                }
+            }
+            if self.latest.movement != map.movement {
+                delta.movement = Some(map.movement.clone());
+                self.latest.movement = map.movement.clone();
+            }
+            if self.latest.overlays != map.overlays {
+                delta.overlays = Some(map.overlays.clone());
+                self.latest.overlays = map.overlays.clone();
+            }
+        }
+
+        if frame % KEYFRAME_EVERY == 0 {
+            self.keyframes.push(map.clone());
+        }
+        self.deltas.push(delta);
+        self.captions.push(caption);
+    }
+}
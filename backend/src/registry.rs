@@ -0,0 +1,180 @@
+use crate::*;
+use std::collections::HashMap;
+
+pub struct Chapter {
+    pub name: String,
+    pub description: String,
+    pub builder: Box<dyn Fn() -> Box<dyn MapGen>>,
+    // Set only while this chapter builds, over anything from the command line
+    pub params: HashMap<String, String>,
+}
+
+impl Chapter {
+    pub(crate) fn viewer(&self) -> Viewer {
+        with_params(&self.params, || Viewer::new((self.builder)()))
+    }
+
+    // Just this chapter, the way its own binary would run it
+    pub fn run(&self, options: &CliOptions) -> BError {
+        with_params(&self.params, || run_with((self.builder)(), options))
+    }
+}
+
+// Every generator the launcher can show, in menu order
+#[derive(Default)]
+pub struct Registry {
+    chapters: Vec<Chapter>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn register(
+        &mut self,
+        name: impl Into<String>,
+        description: impl Into<String>,
+        builder: impl Fn() -> Box<dyn MapGen> + 'static,
+    ) {
+        self.chapters.push(Chapter {
+            name: name.into(),
+            description: description.into(),
+            builder: Box::new(builder),
+            params: HashMap::new(),
+        });
+    }
+
+    // A level from a config file, built by running its steps
+    pub fn register_level(&mut self, level: LevelConfig) {
+        let (name, description) = (level.name.clone(), level.description.clone());
+        self.register(name, description, move || Pipeline::new(level.clone()));
+    }
+
+    pub fn set_params(
+        &mut self,
+        name: &str,
+        params: HashMap<String, String>,
+    ) -> Result<(), ConfigError> {
+        match self.chapters.iter_mut().find(|c| c.name == name) {
+            Some(chapter) => {
+                chapter.params = params;
+                Ok(())
+            }
+            None => Err(ConfigError::UnknownChapter(name.to_string())),
+        }
+    }
+
+    pub fn chapters(&self) -> &[Chapter] {
+        &self.chapters
+    }
+
+    pub fn find(&self, name: &str) -> Option<&Chapter> {
+        self.chapters.iter().find(|c| c.name == name)
+    }
+}
+
+// The menu, or whichever chapter was picked from it until it finishes
+struct Launcher {
+    registry: Registry,
+    selected: usize,
+    // The first chapter shown, once there are too many to fit
+    top: usize,
+    viewer: Option<Viewer>,
+}
+
+impl Launcher {
+    fn menu(&mut self, ctx: &mut BTerm) {
+        ctx.cls();
+        // Chapters listed from row 3, leaving a row for "..." and a blank line
+        // above the description
+        let height = ctx.get_char_size().1 as usize;
+        let menu_rows = height - 9;
+        ctx.print_color_centered(
+            1,
+            RGB::named(YELLOW),
+            RGB::named(BLACK),
+            "Procedural Map Generation",
+        );
+
+        // Scroll just far enough to keep the selection on screen
+        let chapters = self.registry.chapters();
+        if self.selected < self.top {
+            self.top = self.selected;
+        } else if self.selected >= self.top + menu_rows {
+            self.top = self.selected + 1 - menu_rows;
+        }
+        for (i, chapter) in chapters.iter().enumerate().skip(self.top).take(menu_rows) {
+            let color = if i == self.selected {
+                RGB::named(CYAN)
+            } else {
+                RGB::named(GRAY)
+            };
+            ctx.print_color(4, 3 + i - self.top, color, RGB::named(BLACK), &chapter.name);
+        }
+        if self.top > 0 {
+            ctx.print_color(4, 2, RGB::named(GRAY), RGB::named(BLACK), "...");
+        }
+        if self.top + menu_rows < chapters.len() {
+            ctx.print_color(4, 3 + menu_rows, RGB::named(GRAY), RGB::named(BLACK), "...");
+        }
+        if let Some(chapter) = chapters.get(self.selected) {
+            ctx.print_color(
+                4,
+                height - 4,
+                RGB::named(WHITE),
+                RGB::named(BLACK),
+                &chapter.description,
+            );
+        }
+        ctx.print_color_centered(
+            height - 2,
+            RGB::named(GRAY),
+            RGB::named(BLACK),
+            "Up/Down/PgUp/PgDn to choose, Enter to run, Escape to quit",
+        );
+
+        match ctx.key {
+            Some(VirtualKeyCode::Up) if self.selected > 0 => self.selected -= 1,
+            Some(VirtualKeyCode::Down) if self.selected + 1 < chapters.len() => self.selected += 1,
+            Some(VirtualKeyCode::PageUp) => self.selected = self.selected.saturating_sub(menu_rows),
+            Some(VirtualKeyCode::PageDown) => {
+                self.selected =
+                    usize::min(self.selected + menu_rows, chapters.len().saturating_sub(1))
+            }
+            Some(VirtualKeyCode::Return) => {
+                if let Some(chapter) = chapters.get(self.selected) {
+                    self.viewer = Some(chapter.viewer());
+                }
+            }
+            Some(VirtualKeyCode::Escape) => ctx.quit(),
+            _ => {}
+        }
+    }
+}
+
+impl GameState for Launcher {
+    fn tick(&mut self, ctx: &mut BTerm) {
+        match &mut self.viewer {
+            Some(viewer) => {
+                if !viewer.tick(ctx) {
+                    self.viewer = None;
+                }
+            }
+            None => self.menu(ctx),
+        }
+    }
+}
+
+pub fn run_launcher(registry: Registry) -> BError {
+    let context = open_window("Map Builder")?;
+
+    let gs = Launcher {
+        registry,
+        selected: 0,
+        top: 0,
+        viewer: None,
+    };
+
+    main_loop(context, gs)
+}
@@ -0,0 +1,383 @@
+use crate::*;
+use std::collections::HashSet;
+
+// Like Rect::for_each, bounds are inclusive: every shape stays within
+// x1..=x2, y1..=y2.
+pub trait RoomShape {
+    fn bounds(&self) -> Rect;
+    fn points(&self) -> Vec<Point>;
+
+    fn contains(&self, pt: Point) -> bool {
+        self.points().contains(&pt)
+    }
+
+    // The open tile nearest the middle, so corridors always land inside the room
+    fn center(&self) -> Point {
+        let middle = self.bounds().center();
+        self.points()
+            .into_iter()
+            .min_by_key(|pt| (pt.x - middle.x).abs() + (pt.y - middle.y).abs())
+            .unwrap_or(middle)
+    }
+
+    // True if any tile of this room is within padding tiles (diagonals
+    // included) of the other
+    fn intersects(&self, other: &dyn RoomShape, padding: i32) -> bool {
+        let (a, b) = (self.bounds(), other.bounds());
+        if a.x1 - padding > b.x2 || b.x1 - padding > a.x2 {
+            return false;
+        }
+        if a.y1 - padding > b.y2 || b.y1 - padding > a.y2 {
+            return false;
+        }
+        let theirs: HashSet<Point> = other.points().into_iter().collect();
+        self.points().iter().any(|pt| {
+            (-padding..=padding).any(|dy| {
+                (-padding..=padding).any(|dx| theirs.contains(&(*pt + Point::new(dx, dy))))
+            })
+        })
+    }
+
+    fn apply(&self, map: &mut Map, glyph: FontCharType, color: RGB) {
+        for pt in self.points() {
+            if map.in_bounds(pt) {
+                map.set(pt, glyph, color);
+            }
+        }
+    }
+}
+
+impl RoomShape for Rect {
+    fn bounds(&self) -> Rect {
+        *self
+    }
+
+    fn points(&self) -> Vec<Point> {
+        let mut points = Vec::new();
+        self.for_each(|pt| points.push(pt));
+        points
+    }
+
+    fn contains(&self, pt: Point) -> bool {
+        pt.x >= self.x1 && pt.x <= self.x2 && pt.y >= self.y1 && pt.y <= self.y2
+    }
+
+    fn center(&self) -> Point {
+        Rect::center(self)
+    }
+}
+
+pub struct Circle {
+    pub center: Point,
+    pub radius: i32,
+}
+
+impl RoomShape for Circle {
+    fn bounds(&self) -> Rect {
+        Rect::with_exact(
+            self.center.x - self.radius,
+            self.center.y - self.radius,
+            self.center.x + self.radius,
+            self.center.y + self.radius,
+        )
+    }
+
+    fn points(&self) -> Vec<Point> {
+        let limit = (self.radius as f32 + 0.5).powi(2);
+        self.bounds()
+            .points()
+            .into_iter()
+            .filter(|pt| {
+                let (dx, dy) = ((pt.x - self.center.x) as f32, (pt.y - self.center.y) as f32);
+                dx * dx + dy * dy <= limit
+            })
+            .collect()
+    }
+
+    fn center(&self) -> Point {
+        self.center
+    }
+}
+
+pub struct Oval {
+    pub bounds: Rect,
+}
+
+impl RoomShape for Oval {
+    fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    fn points(&self) -> Vec<Point> {
+        let b = self.bounds;
+        let (cx, cy) = ((b.x1 + b.x2) as f32 / 2.0, (b.y1 + b.y2) as f32 / 2.0);
+        let (rx, ry) = (
+            (b.x2 - b.x1) as f32 / 2.0 + 0.5,
+            (b.y2 - b.y1) as f32 / 2.0 + 0.5,
+        );
+        b.points()
+            .into_iter()
+            .filter(|pt| {
+                let (dx, dy) = ((pt.x as f32 - cx) / rx, (pt.y as f32 - cy) / ry);
+                dx * dx + dy * dy <= 1.0
+            })
+            .collect()
+    }
+}
+
+// A plus sign: one bar across the middle and one down it
+pub struct Cross {
+    pub bounds: Rect,
+    pub arm_width: i32,
+}
+
+impl RoomShape for Cross {
+    fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    fn points(&self) -> Vec<Point> {
+        let b = self.bounds;
+        let arm = i32::max(1, self.arm_width);
+        let (left, top) = ((b.x1 + b.x2 + 1 - arm) / 2, (b.y1 + b.y2 + 1 - arm) / 2);
+        b.points()
+            .into_iter()
+            .filter(|pt| (pt.x >= left && pt.x < left + arm) || (pt.y >= top && pt.y < top + arm))
+            .collect()
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+pub enum Corner {
+    TopLeft,
+    TopRight,
+    BottomLeft,
+    BottomRight,
+}
+
+// A rectangle with one quarter missing
+pub struct LShape {
+    pub bounds: Rect,
+    pub missing: Corner,
+}
+
+impl RoomShape for LShape {
+    fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    fn points(&self) -> Vec<Point> {
+        let b = self.bounds;
+        let (mid_x, mid_y) = ((b.x1 + b.x2) / 2, (b.y1 + b.y2) / 2);
+        b.points()
+            .into_iter()
+            .filter(|pt| {
+                let right = pt.x > mid_x;
+                let bottom = pt.y > mid_y;
+                let corner = match (right, bottom) {
+                    (false, false) => Corner::TopLeft,
+                    (true, false) => Corner::TopRight,
+                    (false, true) => Corner::BottomLeft,
+                    (true, true) => Corner::BottomRight,
+                };
+                corner != self.missing
+            })
+            .collect()
+    }
+}
+
+// An irregular cave-like room, made by a drunkard's walk from the middle of
+// bounds until about half of it is open
+pub struct Blob {
+    pub bounds: Rect,
+    pub tiles: Vec<Point>,
+}
+
+impl Blob {
+    pub fn new(bounds: Rect, rng: &mut RandomNumberGenerator) -> Self {
+        let target = ((bounds.width() + 1) * (bounds.height() + 1)) as usize / 2;
+        let mut open = HashSet::new();
+        let mut digger = bounds.center();
+        open.insert(digger);
+        while open.len() < target {
+            let step = match rng.range(0, 4) {
+                0 => Point::new(-1, 0),
+                1 => Point::new(1, 0),
+                2 => Point::new(0, -1),
+                _ => Point::new(0, 1),
+            };
+            let next = digger + step;
+            if bounds.contains(next) {
+                digger = next;
+                open.insert(digger);
+            }
+        }
+        let mut tiles: Vec<Point> = open.into_iter().collect();
+        tiles.sort_by_key(|pt| (pt.y, pt.x));
+        Self { bounds, tiles }
+    }
+}
+
+impl RoomShape for Blob {
+    fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    fn points(&self) -> Vec<Point> {
+        self.tiles.clone()
+    }
+}
+
+// A rectangle with a grid of single-tile pillars, kept clear of the walls so
+// the edges stay walkable
+pub struct PillaredRoom {
+    pub bounds: Rect,
+    pub spacing: i32,
+}
+
+impl RoomShape for PillaredRoom {
+    fn bounds(&self) -> Rect {
+        self.bounds
+    }
+
+    fn points(&self) -> Vec<Point> {
+        let b = self.bounds;
+        let spacing = i32::max(2, self.spacing);
+        b.points()
+            .into_iter()
+            .filter(|pt| {
+                let (x, y) = (pt.x - b.x1, pt.y - b.y1);
+                let inside =
+                    pt.x > b.x1 + 1 && pt.x < b.x2 - 1 && pt.y > b.y1 + 1 && pt.y < b.y2 - 1;
+                !(inside && x % spacing == 0 && y % spacing == 0)
+            })
+            .collect()
+    }
+}
+
+// Picks a shape at random to fill bounds
+pub fn random_room_shape(bounds: Rect, rng: &mut RandomNumberGenerator) -> Box<dyn RoomShape> {
+    let short_side = i32::min(bounds.width(), bounds.height());
+    match rng.range(0, 7) {
+        0 => Box::new(Circle {
+            center: bounds.center(),
+            radius: short_side / 2,
+        }),
+        1 => Box::new(Oval { bounds }),
+        2 => Box::new(Cross {
+            bounds,
+            arm_width: i32::max(1, (short_side + 1) / 3),
+        }),
+        3 => Box::new(LShape {
+            bounds,
+            missing: match rng.range(0, 4) {
+                0 => Corner::TopLeft,
+                1 => Corner::TopRight,
+                2 => Corner::BottomLeft,
+                _ => Corner::BottomRight,
+            },
+        }),
+        4 => Box::new(Blob::new(bounds, rng)),
+        5 => Box::new(PillaredRoom { bounds, spacing: 2 }),
+        _ => Box::new(bounds),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn circle_is_round() {
+        let circle = Circle {
+            center: Point::new(10, 10),
+            radius: 3,
+        };
+        assert!(circle.contains(Point::new(13, 10)));
+        assert!(circle.contains(Point::new(10, 7)));
+        assert!(!circle.contains(Point::new(13, 13)));
+        assert_eq!(circle.center(), Point::new(10, 10));
+    }
+
+    #[test]
+    fn oval_touches_every_side_but_not_the_corners() {
+        let oval = Oval {
+            bounds: Rect::with_exact(0, 0, 8, 4),
+        };
+        for pt in [(0, 2), (8, 2), (4, 0), (4, 4)] {
+            assert!(oval.contains(Point::new(pt.0, pt.1)));
+        }
+        assert!(!oval.contains(Point::new(0, 0)));
+        assert!(!oval.contains(Point::new(8, 4)));
+    }
+
+    #[test]
+    fn cross_has_two_bars_through_the_middle() {
+        let cross = Cross {
+            bounds: Rect::with_exact(0, 0, 8, 8),
+            arm_width: 3,
+        };
+        assert_eq!(cross.points().len(), 9 * 3 * 2 - 3 * 3);
+        assert!(cross.contains(Point::new(0, 4)));
+        assert!(cross.contains(Point::new(4, 8)));
+        assert!(!cross.contains(Point::new(0, 0)));
+        assert!(cross.contains(cross.center()));
+    }
+
+    #[test]
+    fn l_shape_is_missing_one_quarter() {
+        let l = LShape {
+            bounds: Rect::with_exact(0, 0, 8, 8),
+            missing: Corner::TopRight,
+        };
+        assert_eq!(l.points().len(), 81 - 4 * 5);
+        assert!(!l.contains(Point::new(5, 0)));
+        assert!(l.contains(Point::new(4, 0)));
+        assert!(l.contains(Point::new(8, 8)));
+    }
+
+    #[test]
+    fn blob_is_half_open_around_its_center() {
+        let bounds = Rect::with_exact(0, 0, 9, 9);
+        let mut rng = RandomNumberGenerator::seeded(3);
+        let blob = Blob::new(bounds, &mut rng);
+        assert_eq!(blob.points().len(), 50);
+        assert!(blob.points().iter().all(|pt| bounds.contains(*pt)));
+        assert!(blob.contains(blob.center()));
+    }
+
+    #[test]
+    fn pillars_stay_walls() {
+        let room = PillaredRoom {
+            bounds: Rect::with_exact(0, 0, 8, 8),
+            spacing: 2,
+        };
+        let mut map = Map::new();
+        room.apply(&mut map, to_cp437('#'), RGB::named(GREEN));
+        for (x, y) in [(2, 2), (4, 4), (6, 2), (2, 6)] {
+            assert!(map.is_wall(map.idx(x, y)));
+        }
+        for (x, y) in [(1, 1), (3, 4), (0, 4), (8, 8)] {
+            assert!(!map.is_wall(map.idx(x, y)));
+        }
+        assert_eq!(room.points().len(), 81 - 9);
+    }
+
+    #[test]
+    fn padding_counts_tiles_not_bounds() {
+        // The bounds overlap at (12, 12), but the circle's corner and the
+        // L's missing quarter leave three tiles between them
+        let circle = Circle {
+            center: Point::new(10, 10),
+            radius: 2,
+        };
+        let l = LShape {
+            bounds: Rect::with_exact(12, 12, 16, 16),
+            missing: Corner::TopLeft,
+        };
+        assert!(!circle.intersects(&l, 0));
+        assert!(!circle.intersects(&l, 2));
+        assert!(circle.intersects(&l, 3));
+        assert!(l.intersects(&circle, 3));
+    }
+}
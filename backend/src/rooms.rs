@@ -0,0 +1,142 @@
+use crate::*;
+
+// Corridors paint over whatever they cross. The walls_only versions leave open
+// floor as it is, so rooms keep their colors where a corridor runs through.
+pub fn apply_horizontal_tunnel(x1: i32, x2: i32, y: i32, map: &mut Map) {
+    horizontal_tunnel(x1, x2, y, map, false);
+}
+
+pub fn apply_vertical_tunnel(y1: i32, y2: i32, x: i32, map: &mut Map) {
+    vertical_tunnel(y1, y2, x, map, false);
+}
+
+pub fn apply_horizontal_tunnel_walls_only(x1: i32, x2: i32, y: i32, map: &mut Map) {
+    horizontal_tunnel(x1, x2, y, map, true);
+}
+
+pub fn apply_vertical_tunnel_walls_only(y1: i32, y2: i32, x: i32, map: &mut Map) {
+    vertical_tunnel(y1, y2, x, map, true);
+}
+
+fn horizontal_tunnel(x1: i32, x2: i32, y: i32, map: &mut Map, walls_only: bool) {
+    use std::cmp::{max, min};
+    for x in min(x1, x2)..=max(x1, x2) {
+        carve(map, Point::new(x, y), walls_only);
+    }
+}
+
+fn vertical_tunnel(y1: i32, y2: i32, x: i32, map: &mut Map, walls_only: bool) {
+    use std::cmp::{max, min};
+    for y in min(y1, y2)..=max(y1, y2) {
+        carve(map, Point::new(x, y), walls_only);
+    }
+}
+
+fn carve(map: &mut Map, pt: Point, walls_only: bool) {
+    if let Some(idx) = map.try_idx(pt) {
+        if !walls_only || map.is_wall(idx) {
+            map.tiles[idx] = (to_cp437('#'), RGB::named(PURPLE));
+        }
+    }
+}
+
+// Tries count rooms with sides from min_size up to (not including) max_size,
+// discarding any that would touch one already placed, then redraws the keepers
+// sorted from left to right. Whatever was on the map before is kept under them.
+pub fn random_rooms(
+    map: &mut Map,
+    count: usize,
+    min_size: i32,
+    max_size: i32,
+    rng: &mut RandomNumberGenerator,
+    frames: &mut dyn SnapshotRecorder,
+) -> Vec<Rect> {
+    let base = map.clone();
+    let mut rooms = Vec::<(Rect, usize)>::new();
+    let mut room_counter = 0;
+    for _ in 0..count {
+        let room = Rect::with_size(
+            rng.range(1, map.width as i32 - max_size),
+            rng.range(1, map.height as i32 - max_size),
+            rng.range(min_size, max_size),
+            rng.range(min_size, max_size),
+        );
+        let mut overlap = false;
+        for (r, _) in rooms.iter() {
+            let mut r_grow = *r;
+            r_grow.x1 -= 1;
+            r_grow.y1 -= 1;
+            r_grow.x2 += 1;
+            r_grow.y2 += 1;
+            if r_grow.intersect(&room) {
+                overlap = true;
+            }
+        }
+
+        if !overlap {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), iteration_color(room_counter));
+            });
+            rooms.push((room, room_counter));
+            room_counter += 1;
+            frames.record(map, format!("Add room {}", room_counter));
+        } else if frames.is_recording() {
+            let mut discard = map.clone();
+            room.for_each(|p| {
+                discard.set(p, to_cp437('!'), iteration_color(666));
+            });
+            frames.record(&discard, "Discard Frame".to_string());
+        }
+    }
+
+    // Sort it
+    rooms.sort_by_key(|a| a.0.x1);
+    *map = base;
+    for (room, iteration) in rooms.iter() {
+        room.for_each(|p| {
+            map.set(p, to_cp437('#'), iteration_color(*iteration));
+        });
+    }
+    frames.record(map, "Sort Rooms".to_string());
+
+    rooms.into_iter().map(|(room, _)| room).collect()
+}
+
+// Joins each room to the one before it with an L-shaped corridor
+pub fn join_rooms(
+    map: &mut Map,
+    rooms: &[Rect],
+    rng: &mut RandomNumberGenerator,
+    frames: &mut dyn SnapshotRecorder,
+) {
+    for (i, room) in rooms.iter().enumerate().skip(1) {
+        let prev = rooms[i - 1].center();
+        let new = room.center();
+
+        if rng.range(0, 2) == 1 {
+            apply_horizontal_tunnel(prev.x, new.x, prev.y, map);
+            apply_vertical_tunnel(prev.y, new.y, new.x, map);
+        } else {
+            apply_vertical_tunnel(prev.y, new.y, prev.x, map);
+            apply_horizontal_tunnel(prev.x, new.x, new.y, map);
+        }
+        frames.record(map, format!("Corridor {}", i));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn walls_only_tunnels_leave_floor_alone() {
+        let mut map = Map::new();
+        map.set(Point::new(5, 5), to_cp437('~'), RGB::named(BLUE));
+        apply_horizontal_tunnel_walls_only(1, 10, 5, &mut map);
+        assert_eq!(map.tiles[map.idx(5, 5)].0, to_cp437('~'));
+        assert_eq!(map.tiles[map.idx(6, 5)].0, to_cp437('#'));
+
+        apply_vertical_tunnel(1, 10, 5, &mut map);
+        assert_eq!(map.tiles[map.idx(5, 5)].0, to_cp437('#'));
+    }
+}
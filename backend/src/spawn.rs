@@ -0,0 +1,182 @@
+use crate::*;
+
+#[derive(Clone)]
+pub struct Entity {
+    pub name: String,
+    pub glyph: FontCharType,
+    pub color: RGB,
+    pub position: Point,
+}
+
+#[derive(Clone, Copy)]
+pub struct SpawnEntry {
+    pub name: &'static str,
+    pub glyph: char,
+    pub color: (u8, u8, u8),
+    pub weight: i32,
+    pub min_depth: i32,
+    pub max_depth: i32,
+}
+
+pub struct SpawnRules {
+    pub depth: i32,
+    pub start: usize,
+    pub min_distance: f32,
+    pub max_per_area: i32,
+}
+
+pub fn roll_spawn(
+    table: &[SpawnEntry],
+    depth: i32,
+    rng: &mut RandomNumberGenerator,
+) -> Option<SpawnEntry> {
+    let available: Vec<&SpawnEntry> = table
+        .iter()
+        .filter(|e| depth >= e.min_depth && depth <= e.max_depth)
+        .collect();
+    pick_weighted(&available, |e| e.weight, rng).copied()
+}
+
+pub fn spawn_in_rooms(
+    map: &Map,
+    rooms: &[Rect],
+    table: &[SpawnEntry],
+    rules: &SpawnRules,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<Entity> {
+    let areas: Vec<Vec<usize>> = rooms
+        .iter()
+        .map(|room| {
+            let mut tiles = Vec::new();
+            room.for_each(|p| {
+                if let Some(idx) = map.try_idx(p) {
+                    tiles.push(idx);
+                }
+            });
+            tiles
+        })
+        .collect();
+    spawn_in_areas(map, &areas, table, rules, rng)
+}
+
+// Membership holds a region id per tile, such as the seed index of a Voronoi
+// diagram. Tiles in no region are usize::MAX, as Map::regions() gives walls.
+pub fn spawn_in_regions(
+    map: &Map,
+    membership: &[usize],
+    table: &[SpawnEntry],
+    rules: &SpawnRules,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<Entity> {
+    let in_region = || {
+        membership
+            .iter()
+            .enumerate()
+            .filter(|(_, region)| **region != usize::MAX)
+    };
+    let n_regions = in_region()
+        .map(|(_, region)| *region)
+        .max()
+        .map_or(0, |m| m + 1);
+    let mut areas = vec![Vec::new(); n_regions];
+    for (idx, region) in in_region() {
+        areas[*region].push(idx);
+    }
+    spawn_in_areas(map, &areas, table, rules, rng)
+}
+
+fn spawn_in_areas(
+    map: &Map,
+    areas: &[Vec<usize>],
+    table: &[SpawnEntry],
+    rules: &SpawnRules,
+    rng: &mut RandomNumberGenerator,
+) -> Vec<Entity> {
+    // DijkstraMap leaves the start itself unset
+    let mut dijkstra = DijkstraMap::new(map.width, map.height, &[rules.start], map, DIJKSTRA_DEPTH);
+    dijkstra.map[rules.start] = 0.0;
+    let mut occupied = vec![false; map.tiles.len()];
+    occupied[rules.start] = true;
+
+    let mut entities = Vec::new();
+    for area in areas.iter() {
+        let mut candidates: Vec<usize> = area
+            .iter()
+            .filter(|idx| !map.is_wall(**idx) && !occupied[**idx])
+            .filter(|idx| {
+                let d = dijkstra.map[**idx];
+                d >= rules.min_distance && d < f32::MAX
+            })
+            .copied()
+            .collect();
+
+        let n_spawns = rng.range(0, rules.max_per_area + 1);
+        for _ in 0..n_spawns {
+            if candidates.is_empty() {
+                break;
+            }
+            let idx = candidates.remove(rng.random_slice_index(&candidates).unwrap());
+            if let Some(entry) = roll_spawn(table, rules.depth, rng) {
+                occupied[idx] = true;
+                entities.push(Entity {
+                    name: entry.name.to_string(),
+                    glyph: to_cp437(entry.glyph),
+                    color: RGB::named(entry.color),
+                    position: map.point(idx),
+                });
+            }
+        }
+    }
+    entities
+}
+
+pub const SPAWN_TABLE: &[SpawnEntry] = &[
+    SpawnEntry {
+        name: "Rat",
+        glyph: 'r',
+        color: BROWN1,
+        weight: 10,
+        min_depth: 1,
+        max_depth: 3,
+    },
+    SpawnEntry {
+        name: "Goblin",
+        glyph: 'g',
+        color: GREEN,
+        weight: 8,
+        min_depth: 1,
+        max_depth: 6,
+    },
+    SpawnEntry {
+        name: "Orc",
+        glyph: 'o',
+        color: ORANGE,
+        weight: 6,
+        min_depth: 3,
+        max_depth: 10,
+    },
+    SpawnEntry {
+        name: "Ogre",
+        glyph: 'O',
+        color: RED,
+        weight: 2,
+        min_depth: 6,
+        max_depth: 100,
+    },
+    SpawnEntry {
+        name: "Health Potion",
+        glyph: '!',
+        color: MAGENTA,
+        weight: 5,
+        min_depth: 1,
+        max_depth: 100,
+    },
+    SpawnEntry {
+        name: "Scroll",
+        glyph: '?',
+        color: CYAN,
+        weight: 3,
+        min_depth: 2,
+        max_depth: 100,
+    },
+];
@@ -0,0 +1,213 @@
+use crate::*;
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum BuildingKind {
+    Tavern,
+    Temple,
+    Blacksmith,
+    GeneralStore,
+    Alchemist,
+    House,
+}
+
+impl BuildingKind {
+    pub fn name(&self) -> &'static str {
+        match self {
+            BuildingKind::Tavern => "Tavern",
+            BuildingKind::Temple => "Temple",
+            BuildingKind::Blacksmith => "Blacksmith",
+            BuildingKind::GeneralStore => "General Store",
+            BuildingKind::Alchemist => "Alchemist",
+            BuildingKind::House => "House",
+        }
+    }
+
+    fn floor_color(&self) -> RGB {
+        match self {
+            BuildingKind::Tavern => RGB::named(ORANGE),
+            BuildingKind::Temple => RGB::named(WHITE),
+            BuildingKind::Blacksmith => RGB::named(RED),
+            BuildingKind::GeneralStore => RGB::named(YELLOW),
+            BuildingKind::Alchemist => RGB::named(MAGENTA),
+            BuildingKind::House => RGB::named(BURLYWOOD),
+        }
+    }
+}
+
+// The footprint includes the walls; the door sits in one of them
+pub struct Building {
+    pub kind: BuildingKind,
+    pub footprint: Rect,
+    pub door: Point,
+}
+
+pub struct Town {
+    pub buildings: Vec<Building>,
+    pub square: Rect,
+    pub walls: Rect,
+    pub gates: Vec<Point>,
+    pub entrance: Point,
+}
+
+// The buildings nearest the square get the interesting jobs
+const SHOPS: [BuildingKind; 5] = [
+    BuildingKind::Tavern,
+    BuildingKind::GeneralStore,
+    BuildingKind::Temple,
+    BuildingKind::Blacksmith,
+    BuildingKind::Alchemist,
+];
+
+fn grow(rect: &Rect, by: i32) -> Rect {
+    Rect::with_exact(rect.x1 - by, rect.y1 - by, rect.x2 + by, rect.y2 + by)
+}
+
+fn outline(rect: &Rect, pt: Point) -> bool {
+    pt.x == rect.x1 || pt.x == rect.x2 || pt.y == rect.y1 || pt.y == rect.y2
+}
+
+// A door somewhere along whichever wall faces the target, and the way out
+// through it
+fn pick_door(footprint: &Rect, target: Point, rng: &mut RandomNumberGenerator) -> (Point, Point) {
+    let center = footprint.center();
+    let (dx, dy) = (target.x - center.x, target.y - center.y);
+    let along_x = rng.range(footprint.x1 + 1, footprint.x2);
+    let along_y = rng.range(footprint.y1 + 1, footprint.y2);
+    if dx.abs() > dy.abs() {
+        if dx < 0 {
+            (Point::new(footprint.x1, along_y), Point::new(-1, 0))
+        } else {
+            (Point::new(footprint.x2, along_y), Point::new(1, 0))
+        }
+    } else if dy < 0 {
+        (Point::new(along_x, footprint.y1), Point::new(0, -1))
+    } else {
+        (Point::new(along_x, footprint.y2), Point::new(0, 1))
+    }
+}
+
+// Roads follow A* over the grass, which costs more than road to cross, so
+// later roads tend to join earlier ones. Doors are expensive so roads don't
+// cut through other people's houses.
+fn lay_road(map: &mut Map, from: Point, to: Point) {
+    let mut planner = map.clone();
+    planner.movement.tile_costs.push((to_cp437('+'), 50.0));
+    let path = a_star_search(map.idx(from.x, from.y), map.idx(to.x, to.y), &planner);
+    if path.success {
+        for idx in path.steps {
+            if map.tiles[idx].0 == to_cp437('"') {
+                map.tiles[idx] = (to_cp437('#'), RGB::named(SANDY_BROWN));
+            }
+        }
+    }
+}
+
+impl Town {
+    pub fn generate(
+        rng: &mut RandomNumberGenerator,
+        frames: &mut dyn SnapshotRecorder,
+    ) -> (Map, Self) {
+        let mut map = Map::new();
+        map.clear(to_cp437('"'), RGB::named(GREEN));
+        map.movement.tile_costs = vec![(to_cp437('"'), 2.0)];
+
+        // The town sits to the west, leaving room for the dungeon to the east
+        let walls = Rect::with_exact(2, 2, map.width as i32 * 3 / 4, map.height as i32 - 3);
+        let gates = vec![
+            Point::new(walls.x1, rng.range(walls.y1 + 5, walls.y2 - 5)),
+            Point::new(walls.x2, rng.range(walls.y1 + 5, walls.y2 - 5)),
+        ];
+        walls.for_each(|pt| {
+            if outline(&walls, pt) {
+                map.set(pt, to_cp437('.'), RGB::named(DARK_GRAY));
+            }
+        });
+        for gate in gates.iter() {
+            map.set(*gate, to_cp437('+'), RGB::named(BROWN1));
+        }
+        frames.record(&map, "Town Walls".to_string());
+
+        let middle = walls.center();
+        let square = Rect::with_exact(middle.x - 6, middle.y - 4, middle.x + 6, middle.y + 4);
+        square.for_each(|pt| {
+            map.set(pt, to_cp437('#'), RGB::named(LIGHT_GRAY));
+        });
+        frames.record(&map, "Town Square".to_string());
+
+        // Buildings keep a couple of tiles clear all round for roads
+        let mut footprints: Vec<Rect> = Vec::new();
+        for _ in 0..300 {
+            let footprint = Rect::with_size(
+                rng.range(walls.x1 + 3, walls.x2 - 13),
+                rng.range(walls.y1 + 3, walls.y2 - 10),
+                rng.range(5, 11),
+                rng.range(4, 8),
+            );
+            let clear = !grow(&footprint, 2).intersect(&square)
+                && !footprints.iter().any(|f| grow(f, 2).intersect(&footprint));
+            if clear {
+                footprints.push(footprint);
+            }
+        }
+        footprints.sort_by_key(|f| {
+            let c = f.center();
+            (c.x - middle.x).abs() + (c.y - middle.y).abs()
+        });
+
+        let mut buildings = Vec::new();
+        let mut doorsteps = Vec::new();
+        for (i, footprint) in footprints.into_iter().enumerate() {
+            let kind = if i < SHOPS.len() {
+                SHOPS[i]
+            } else {
+                BuildingKind::House
+            };
+            footprint.for_each(|pt| {
+                if outline(&footprint, pt) {
+                    map.set(pt, to_cp437('.'), RGB::named(DARK_GRAY));
+                } else {
+                    map.set(pt, to_cp437('#'), kind.floor_color());
+                }
+            });
+            let (door, outward) = pick_door(&footprint, middle, rng);
+            map.set(door, to_cp437('+'), RGB::named(BROWN1));
+            doorsteps.push(door + outward);
+            buildings.push(Building {
+                kind,
+                footprint,
+                door,
+            });
+            frames.record(&map, format!("Build {}", kind.name()));
+        }
+
+        for doorstep in doorsteps {
+            lay_road(&mut map, doorstep, middle);
+        }
+        frames.record(&map, "Roads to the Square".to_string());
+
+        // Out through the gates: one road off the map, one to the dungeon
+        let entrance = Point::new(
+            rng.range(walls.x2 + 6, map.width as i32 - 3),
+            rng.range(5, map.height as i32 - 5),
+        );
+        map.set(entrance, to_cp437('>'), RGB::named(GOLD));
+        lay_road(&mut map, gates[0] + Point::new(1, 0), middle);
+        lay_road(
+            &mut map,
+            gates[0] + Point::new(-1, 0),
+            Point::new(0, gates[0].y),
+        );
+        lay_road(&mut map, gates[1] + Point::new(-1, 0), middle);
+        lay_road(&mut map, gates[1] + Point::new(1, 0), entrance);
+        frames.record(&map, "Roads to the Gates and Dungeon".to_string());
+
+        let town = Town {
+            buildings,
+            square,
+            walls,
+            gates,
+            entrance,
+        };
+        (map, town)
+    }
+}
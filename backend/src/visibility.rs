@@ -0,0 +1,87 @@
+use crate::*;
+
+// How far anything lurking near the path can see, or be seen from
+const AMBUSH_RANGE: i32 = 8;
+
+#[derive(Clone)]
+pub struct Visibility {
+    pub from_start: Vec<bool>,
+    pub from_rooms: Vec<Vec<bool>>,
+    // Of the open tiles, how many can be seen from the start
+    pub visible_fraction: f32,
+    // How many open tiles within AMBUSH_RANGE each tile can see
+    pub exposure: Vec<usize>,
+    pub ambush: Vec<usize>,
+}
+
+fn visible_from(map: &Map, idx: usize, range: i32) -> Vec<bool> {
+    let mut visible = vec![false; map.tiles.len()];
+    let pt = map.point(idx);
+    for seen in field_of_view(pt, range, map) {
+        if let Some(seen) = map.try_idx(seen) {
+            visible[seen] = true;
+        }
+    }
+    visible
+}
+
+// Ambush tiles are open tiles a short walk from the path that nobody on it can
+// see, but that can see more than most of the map around them.
+pub fn visibility_analysis(map: &Map, start: usize, rooms: &[Rect], path: &[usize]) -> Visibility {
+    let whole_map = (map.width + map.height) as i32;
+    let from_start = visible_from(map, start, whole_map);
+    let from_rooms = rooms
+        .iter()
+        .map(|r| {
+            let center = r.center();
+            visible_from(map, map.idx(center.x, center.y), whole_map)
+        })
+        .collect();
+
+    let open: Vec<usize> = (0..map.tiles.len())
+        .filter(|idx| !map.is_wall(*idx))
+        .collect();
+    let visible_fraction = if open.is_empty() {
+        0.0
+    } else {
+        open.iter().filter(|idx| from_start[**idx]).count() as f32 / open.len() as f32
+    };
+
+    let mut exposure = vec![0; map.tiles.len()];
+    for idx in open.iter() {
+        exposure[*idx] = visible_from(map, *idx, AMBUSH_RANGE)
+            .iter()
+            .enumerate()
+            .filter(|(i, seen)| **seen && !map.is_wall(*i))
+            .count();
+    }
+    let average = if open.is_empty() {
+        0
+    } else {
+        open.iter().map(|idx| exposure[*idx]).sum::<usize>() / open.len()
+    };
+
+    let mut seen_from_path = vec![false; map.tiles.len()];
+    for step in path.iter() {
+        for (i, seen) in visible_from(map, *step, AMBUSH_RANGE).iter().enumerate() {
+            seen_from_path[i] |= *seen;
+        }
+    }
+    let near_path = DijkstraMap::new(map.width, map.height, path, map, DIJKSTRA_DEPTH).map;
+    let ambush = open
+        .into_iter()
+        .filter(|idx| {
+            !seen_from_path[*idx]
+                && near_path[*idx] <= AMBUSH_RANGE as f32
+                && exposure[*idx] > average
+        })
+        .collect();
+
+    Visibility {
+        from_start,
+        from_rooms,
+        visible_fraction,
+        exposure,
+        ambush,
+    }
+}
@@ -0,0 +1,433 @@
+use crate::*;
+use std::collections::HashMap;
+use std::fmt;
+
+// How many collapse steps we can undo before giving up and starting over
+const MAX_BACKTRACK: usize = 16;
+
+// Opposite directions differ only in the lowest bit
+const NEIGHBORS: [(i32, i32); 4] = [(-1, 0), (1, 0), (0, -1), (0, 1)];
+
+#[derive(Debug)]
+pub enum WfcError {
+    PatternSize(usize),
+    Contradiction,
+}
+
+impl fmt::Display for WfcError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            WfcError::PatternSize(n) => {
+                write!(f, "A {0}x{0} pattern doesn't fit the sample or the map", n)
+            }
+            WfcError::Contradiction => write!(f, "Every attempt ended in a contradiction"),
+        }
+    }
+}
+
+impl std::error::Error for WfcError {}
+
+struct Wave {
+    possible: Vec<Vec<bool>>,
+    remaining: Vec<usize>,
+    // support[(cell * patterns + p) * 4 + d] counts the patterns still possible
+    // in the neighbor in direction d that p can sit next to
+    support: Vec<u32>,
+    // Every pattern ruled out so far, so backtracking can put them back
+    trail: Vec<(usize, usize)>,
+    pending: Vec<(usize, usize)>,
+}
+
+// Overlapping-model WFC: learns every NxN window of a sample and builds maps in
+// which every NxN window is one of them.
+pub struct WaveFunctionCollapse {
+    pub n: usize,
+    pub max_attempts: usize,
+    // Push a frame every this many collapse steps
+    pub frame_every: Option<usize>,
+    // The size of the maps it makes, from Map::new when it learned
+    width: usize,
+    height: usize,
+    patterns: Vec<Vec<FontCharType>>,
+    weights: Vec<usize>,
+    palette: Vec<(FontCharType, RGB)>,
+    // allowed[p][d] lists the patterns that can sit next to p in NEIGHBORS[d]
+    allowed: Vec<[Vec<usize>; 4]>,
+}
+
+impl WaveFunctionCollapse {
+    pub fn from_map(sample: &Map, n: usize) -> Result<Self, WfcError> {
+        Self::learn(&sample.tiles, sample.width, sample.height, n, false)
+    }
+
+    // Uses the same template characters as apply_prefab. Templates wrap
+    // around, so the right edge is read as touching the left (and the bottom
+    // the top); a small tileable template gives far more variety that way. The
+    // template is drawn on a map of the current size, so it can't be bigger.
+    pub fn from_template(
+        template: &str,
+        width: usize,
+        height: usize,
+        n: usize,
+        floor: RGB,
+    ) -> Result<Self, WfcError> {
+        let mut sample = Map::new();
+        if width > sample.width || height > sample.height {
+            return Err(WfcError::PatternSize(n));
+        }
+        apply_prefab(&mut sample, template, width, Point::zero(), floor);
+        let tiles: Vec<(FontCharType, RGB)> = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| sample.tiles[(y * sample.width) + x])
+            .collect();
+        Self::learn(&tiles, width, height, n, true)
+    }
+
+    // Every pattern has to fit inside the sample and the map, or there's
+    // nothing to learn and nowhere to put it
+    fn learn(
+        tiles: &[(FontCharType, RGB)],
+        width: usize,
+        height: usize,
+        n: usize,
+        wrap: bool,
+    ) -> Result<Self, WfcError> {
+        let (map_width, map_height) = map_size();
+        if n == 0 || n > usize::min(width, height) || n > usize::min(map_width, map_height) {
+            return Err(WfcError::PatternSize(n));
+        }
+
+        let mut palette: Vec<(FontCharType, RGB)> = Vec::new();
+        for (glyph, color) in tiles.iter() {
+            if !palette.iter().any(|(g, _)| g == glyph) {
+                palette.push((*glyph, *color));
+            }
+        }
+
+        let mut patterns = Vec::new();
+        let mut weights = Vec::new();
+        let mut seen: HashMap<Vec<FontCharType>, usize> = HashMap::new();
+        let (max_x, max_y) = if wrap {
+            (width - 1, height - 1)
+        } else {
+            (width - n, height - n)
+        };
+        for y in 0..=max_y {
+            for x in 0..=max_x {
+                let pattern: Vec<FontCharType> = (0..n * n)
+                    .map(|i| {
+                        let (px, py) = ((x + i % n) % width, (y + i / n) % height);
+                        tiles[(py * width) + px].0
+                    })
+                    .collect();
+                if let Some(id) = seen.get(&pattern) {
+                    weights[*id] += 1;
+                } else {
+                    seen.insert(pattern.clone(), patterns.len());
+                    patterns.push(pattern);
+                    weights.push(1);
+                }
+            }
+        }
+
+        // Two patterns can be neighbors if they agree everywhere they overlap
+        let agrees = |p: &[FontCharType], q: &[FontCharType], dx: i32, dy: i32| {
+            let n = n as i32;
+            (0..n * n).all(|i| {
+                let (x, y) = (i % n, i / n);
+                let (qx, qy) = (x - dx, y - dy);
+                qx < 0 || qy < 0 || qx >= n || qy >= n || p[i as usize] == q[(qy * n + qx) as usize]
+            })
+        };
+        let allowed = patterns
+            .iter()
+            .map(|p| {
+                let mut allowed: [Vec<usize>; 4] = Default::default();
+                for (d, (dx, dy)) in NEIGHBORS.iter().enumerate() {
+                    allowed[d] = (0..patterns.len())
+                        .filter(|q| agrees(p, &patterns[*q], *dx, *dy))
+                        .collect();
+                }
+                allowed
+            })
+            .collect();
+
+        Ok(Self {
+            n,
+            max_attempts: 10,
+            frame_every: None,
+            width: map_width,
+            height: map_height,
+            patterns,
+            weights,
+            palette,
+            allowed,
+        })
+    }
+
+    pub fn pattern_count(&self) -> usize {
+        self.patterns.len()
+    }
+
+    fn cells(&self) -> (usize, usize) {
+        (self.width - self.n + 1, self.height - self.n + 1)
+    }
+
+    fn neighbor(&self, cell: usize, d: usize) -> Option<usize> {
+        let (cells_w, cells_h) = self.cells();
+        let x = (cell % cells_w) as i32 + NEIGHBORS[d].0;
+        let y = (cell / cells_w) as i32 + NEIGHBORS[d].1;
+        if x < 0 || y < 0 || x >= cells_w as i32 || y >= cells_h as i32 {
+            None
+        } else {
+            Some((y as usize * cells_w) + x as usize)
+        }
+    }
+
+    fn support_idx(&self, cell: usize, pattern: usize, d: usize) -> usize {
+        (((cell * self.patterns.len()) + pattern) * 4) + d
+    }
+
+    fn new_wave(&self) -> Wave {
+        let (cells_w, cells_h) = self.cells();
+        let n_cells = cells_w * cells_h;
+        let mut support = vec![0; n_cells * self.patterns.len() * 4];
+        for cell in 0..n_cells {
+            for p in 0..self.patterns.len() {
+                for d in 0..4 {
+                    // Nothing past the edge can ever take support away
+                    support[self.support_idx(cell, p, d)] = match self.neighbor(cell, d) {
+                        Some(_) => self.allowed[p][d].len() as u32,
+                        None => u32::MAX,
+                    };
+                }
+            }
+        }
+        Wave {
+            possible: vec![vec![true; self.patterns.len()]; n_cells],
+            remaining: vec![self.patterns.len(); n_cells],
+            support,
+            trail: Vec::new(),
+            pending: Vec::new(),
+        }
+    }
+
+    pub fn generate(
+        &self,
+        rng: &mut RandomNumberGenerator,
+        frames: &mut dyn SnapshotRecorder,
+    ) -> Result<Map, WfcError> {
+        let (cells_w, cells_h) = self.cells();
+        let n_cells = cells_w * cells_h;
+
+        let mut step = 0;
+        for attempt in 1..=self.max_attempts {
+            let mut wave = self.new_wave();
+            let mut history: Vec<(usize, usize, usize)> = Vec::new();
+
+            loop {
+                // Collapse whichever undecided cell has the fewest options left
+                let fewest = wave.remaining.iter().filter(|r| **r > 1).min();
+                let fewest = match fewest {
+                    Some(fewest) => *fewest,
+                    None => return Ok(self.render(&wave)),
+                };
+                let candidates: Vec<usize> = (0..n_cells)
+                    .filter(|c| wave.remaining[*c] == fewest)
+                    .collect();
+                let cell = *rng.random_slice_entry(&candidates).unwrap();
+                let pattern = self.pick_pattern(&wave.possible[cell], rng);
+
+                history.push((wave.trail.len(), cell, pattern));
+                if history.len() > MAX_BACKTRACK {
+                    history.remove(0);
+                }
+                for p in 0..self.patterns.len() {
+                    if p != pattern && wave.possible[cell][p] {
+                        self.ban(&mut wave, cell, p);
+                    }
+                }
+
+                // On a contradiction, rewind and rule out the choice that caused it
+                let mut ok = self.propagate(&mut wave);
+                while !ok {
+                    match history.pop() {
+                        Some((mark, cell, pattern)) => {
+                            self.undo(&mut wave, mark);
+                            self.ban(&mut wave, cell, pattern);
+                            ok = wave.remaining[cell] > 0 && self.propagate(&mut wave);
+                        }
+                        None => break,
+                    }
+                }
+                if !ok {
+                    if self.frame_every.is_some() && frames.is_recording() {
+                        frames.record(&self.render(&wave), format!("WFC Restart {}", attempt));
+                    }
+                    break;
+                }
+
+                step += 1;
+                if let Some(every) = self.frame_every {
+                    if step % every == 0 && frames.is_recording() {
+                        frames.record(&self.render(&wave), format!("WFC Step {}", step));
+                    }
+                }
+            }
+        }
+
+        Err(WfcError::Contradiction)
+    }
+
+    fn pick_pattern(&self, possible: &[bool], rng: &mut RandomNumberGenerator) -> usize {
+        let total: usize = (0..possible.len())
+            .filter(|p| possible[*p])
+            .map(|p| self.weights[p])
+            .sum();
+        let mut roll = rng.range(0, total as i32) as usize;
+        for p in (0..possible.len()).filter(|p| possible[*p]) {
+            if roll < self.weights[p] {
+                return p;
+            }
+            roll -= self.weights[p];
+        }
+        0
+    }
+
+    // Anything left with no support from a neighbor is queued to be banned too
+    fn ban(&self, wave: &mut Wave, cell: usize, pattern: usize) {
+        wave.possible[cell][pattern] = false;
+        wave.remaining[cell] -= 1;
+        wave.trail.push((cell, pattern));
+        for d in 0..4 {
+            if let Some(neighbor) = self.neighbor(cell, d) {
+                for q in self.allowed[pattern][d].iter() {
+                    let idx = self.support_idx(neighbor, *q, d ^ 1);
+                    wave.support[idx] -= 1;
+                    if wave.support[idx] == 0 && wave.possible[neighbor][*q] {
+                        wave.pending.push((neighbor, *q));
+                    }
+                }
+            }
+        }
+    }
+
+    fn undo(&self, wave: &mut Wave, mark: usize) {
+        wave.pending.clear();
+        while wave.trail.len() > mark {
+            let (cell, pattern) = wave.trail.pop().unwrap();
+            wave.possible[cell][pattern] = true;
+            wave.remaining[cell] += 1;
+            for d in 0..4 {
+                if let Some(neighbor) = self.neighbor(cell, d) {
+                    for q in self.allowed[pattern][d].iter() {
+                        let idx = self.support_idx(neighbor, *q, d ^ 1);
+                        wave.support[idx] += 1;
+                    }
+                }
+            }
+        }
+    }
+
+    // Returns false if some cell runs out of patterns
+    fn propagate(&self, wave: &mut Wave) -> bool {
+        while let Some((cell, pattern)) = wave.pending.pop() {
+            if wave.possible[cell][pattern] {
+                self.ban(wave, cell, pattern);
+                if wave.remaining[cell] == 0 {
+                    wave.pending.clear();
+                    return false;
+                }
+            }
+        }
+        true
+    }
+
+    // Each tile comes from the cell that covers it, with the last row and
+    // column of cells filling in the edges. Undecided tiles show as '?'.
+    fn render(&self, wave: &Wave) -> Map {
+        let (cells_w, cells_h) = self.cells();
+        let mut map = Map::with_size(self.width, self.height);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                let (cx, cy) = (usize::min(x, cells_w - 1), usize::min(y, cells_h - 1));
+                let cell = (cy * cells_w) + cx;
+                let offset = ((y - cy) * self.n) + x - cx;
+                let mut glyphs = (0..self.patterns.len())
+                    .filter(|p| wave.possible[cell][*p])
+                    .map(|p| self.patterns[p][offset]);
+                let glyph = glyphs.next();
+                let tile = match glyph {
+                    Some(glyph) if glyphs.all(|g| g == glyph) => {
+                        *self.palette.iter().find(|(g, _)| *g == glyph).unwrap()
+                    }
+                    _ => (to_cp437('?'), RGB::named(SLATE_GRAY)),
+                };
+                map.tiles[(y * self.width) + x] = tile;
+            }
+        }
+        map
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ROOM: &str = "
+####
+#..#
+#..#
+####
+";
+
+    #[test]
+    fn patterns_have_to_fit() {
+        let checker = |n| WaveFunctionCollapse::from_template("#.\n.#", 2, 2, n, RGB::named(GREEN));
+        assert!(matches!(checker(0), Err(WfcError::PatternSize(0))));
+        assert!(matches!(checker(3), Err(WfcError::PatternSize(3))));
+        let too_tall = WaveFunctionCollapse::from_map(&Map::with_size(90, 90), DEFAULT_HEIGHT + 1);
+        assert!(matches!(too_tall, Err(WfcError::PatternSize(_))));
+        let too_wide = WaveFunctionCollapse::from_template(
+            &"#.".repeat(DEFAULT_WIDTH),
+            DEFAULT_WIDTH * 2,
+            1,
+            1,
+            RGB::named(GREEN),
+        );
+        assert!(matches!(too_wide, Err(WfcError::PatternSize(1))));
+    }
+
+    #[test]
+    fn checkerboard_makes_a_checkerboard() {
+        let wfc =
+            WaveFunctionCollapse::from_template("#.\n.#", 2, 2, 2, RGB::named(GREEN)).unwrap();
+        assert_eq!(wfc.pattern_count(), 2);
+        let mut rng = RandomNumberGenerator::seeded(1);
+        let map = wfc.generate(&mut rng, &mut NullRecorder).unwrap();
+        for y in 0..map.height {
+            for x in 0..map.width {
+                let other = if x + 1 < map.width { x + 1 } else { x - 1 };
+                assert_ne!(
+                    map.tiles[(y * map.width) + x].0,
+                    map.tiles[(y * map.width) + other].0
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn every_window_comes_from_the_sample() {
+        let wfc = WaveFunctionCollapse::from_template(ROOM, 4, 4, 3, RGB::named(GREEN)).unwrap();
+        let mut rng = RandomNumberGenerator::seeded(1);
+        let map = wfc.generate(&mut rng, &mut NullRecorder).unwrap();
+        for y in 0..=map.height - wfc.n {
+            for x in 0..=map.width - wfc.n {
+                let window: Vec<FontCharType> = (0..wfc.n * wfc.n)
+                    .map(|i| map.tiles[((y + i / wfc.n) * map.width) + x + i % wfc.n].0)
+                    .collect();
+                assert!(wfc.patterns.contains(&window), "({}, {})", x, y);
+            }
+        }
+    }
+}
@@ -0,0 +1,29 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Random Rooms",
+        "Rooms scattered at random, then joined by corridors in order",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        let rooms = random_rooms(&mut map, param("rooms", 20), 2, 10, &mut rng, frames);
+        join_rooms(&mut map, &rooms, &mut rng, frames);
+    }
+}
@@ -0,0 +1,159 @@
+use backend::*;
+
+pub struct RoomBuilder {
+    rects: Vec<Rect>,
+    rooms: Vec<Rect>,
+}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            rects: Vec::new(),
+            rooms: Vec::new(),
+        })
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "BSP Rooms",
+        "Space split into quarters, with a room in each piece",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        // Make the first room
+        let mut map = Map::new();
+        self.rects.push(Rect::with_size(
+            2,
+            2,
+            map.width as i32 - 5,
+            map.height as i32 - 5,
+        ));
+
+        // Divide
+        let first_room = self.rects[0];
+        self.add_subrects(first_room);
+
+        for (i, room) in self.rects.iter().enumerate() {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), iteration_color(i));
+            });
+            frames.record(&map, "Subdivide Starting Room".to_string());
+        }
+
+        let mut rng = new_rng();
+        let mut map = Map::new();
+        let mut n_rooms = 0;
+        while n_rooms < param("attempts", 240) {
+            let rect = self.get_random_rect(&mut rng);
+            let candidate = self.get_random_sub_rect(rect, &mut rng);
+            if self.is_possible(candidate, &map) {
+                candidate.for_each(|p| {
+                    map.set(p, to_cp437('#'), iteration_color(n_rooms % 12));
+                });
+                self.rooms.push(candidate);
+                self.add_subrects(rect);
+                frames.record(&map, "Add Room".to_string());
+            }
+            n_rooms += 1;
+        }
+
+        // Sort it
+        self.rooms.sort_by_key(|a| a.x1);
+        map.clear_default();
+        for (iteration, room) in self.rooms.iter().enumerate() {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), iteration_color(iteration));
+            });
+        }
+        frames.record(&map, "Sort Rooms".to_string());
+
+        // Corridors
+        for (i, room) in self.rooms.iter().enumerate().skip(1) {
+            let prev = self.rooms[i - 1].center();
+            let new = room.center();
+
+            if rng.range(0, 2) == 1 {
+                apply_horizontal_tunnel(prev.x, new.x, prev.y, &mut map);
+                apply_vertical_tunnel(prev.y, new.y, new.x, &mut map);
+            } else {
+                apply_vertical_tunnel(prev.y, new.y, prev.x, &mut map);
+                apply_horizontal_tunnel(prev.x, new.x, new.y, &mut map);
+            }
+            frames.record(&map, format!("Corridor {}", i));
+        }
+    }
+}
+
+impl RoomBuilder {
+    fn add_subrects(&mut self, rect: Rect) {
+        self.rects.extend_from_slice(&subdivide(rect));
+    }
+
+    fn get_random_rect(&mut self, rng: &mut RandomNumberGenerator) -> Rect {
+        if self.rects.len() == 1 {
+            return self.rects[0];
+        }
+        let idx = (rng.roll_dice(1, self.rects.len() as i32) - 1) as usize;
+        self.rects[idx]
+    }
+
+    fn get_random_sub_rect(&self, rect: Rect, rng: &mut RandomNumberGenerator) -> Rect {
+        let mut result = rect;
+        let rect_width = i32::abs(rect.x1 - rect.x2);
+        let rect_height = i32::abs(rect.y1 - rect.y2);
+
+        let w = i32::max(3, rng.roll_dice(1, i32::min(rect_width, 10)) - 1) + 1;
+        let h = i32::max(3, rng.roll_dice(1, i32::min(rect_height, 10)) - 1) + 1;
+
+        result.x1 += rng.roll_dice(1, 6) - 1;
+        result.y1 += rng.roll_dice(1, 6) - 1;
+        result.x2 = result.x1 + w;
+        result.y2 = result.y1 + h;
+
+        result
+    }
+
+    fn is_possible(&self, rect: Rect, map: &Map) -> bool {
+        let mut expanded = rect;
+        expanded.x1 -= 2;
+        expanded.x2 += 2;
+        expanded.y1 -= 2;
+        expanded.y2 += 2;
+
+        let mut can_build = true;
+
+        for y in expanded.y1..=expanded.y2 {
+            for x in expanded.x1..=expanded.x2 {
+                if x > map.width as i32 - 2 {
+                    can_build = false;
+                }
+                if y > map.height as i32 - 2 {
+                    can_build = false;
+                }
+                if x < 1 {
+                    can_build = false;
+                }
+                if y < 1 {
+                    can_build = false;
+                }
+                if can_build {
+                    if let Some(idx) = map.try_idx(Point::new(x, y)) {
+                        if map.tiles[idx].0 != to_cp437('.') {
+                            can_build = false;
+                        }
+                    } else {
+                        can_build = false;
+                    }
+                }
+            }
+        }
+
+        can_build
+    }
+}
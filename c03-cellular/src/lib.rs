@@ -0,0 +1,34 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Cellular Automata",
+        "Random noise smoothed into caves, one iteration at a time",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut map = Map::new();
+        let mut rng = new_rng();
+        let fill = param("fill", 55);
+        random_fill(&mut map, fill, &mut rng);
+        frames.record(&map, format!("Random Noise - {}% Walls", fill));
+
+        for i in 0..param("iterations", 10) {
+            cellular_iterate(&mut map);
+            frames.record(&map, format!("Iteration {}", i + 1));
+        }
+    }
+}
@@ -0,0 +1,32 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Drunkard's Walk",
+        "Diggers stagger about until a third of the map is open",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        frames.record(&map, "Start Solid".to_string());
+
+        // Keep digging until this much of the map is open
+        let target = param("open", 1.0f32 / 3.0).min(0.9);
+        dig_until_open(&mut map, target, &mut rng, frames);
+    }
+}
@@ -0,0 +1,92 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Diffusion-Limited Aggregation",
+        "Particles wander in from anywhere and stick to the open area",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        let starting_point = Point::new(map.width / 2, map.height / 2);
+        map.set(starting_point, to_cp437('#'), RGB::named(GREEN));
+        map.set(
+            starting_point + Point::new(1, 0),
+            to_cp437('#'),
+            RGB::named(GREEN),
+        );
+        map.set(
+            starting_point + Point::new(-1, 0),
+            to_cp437('#'),
+            RGB::named(GREEN),
+        );
+        map.set(
+            starting_point + Point::new(0, 1),
+            to_cp437('#'),
+            RGB::named(GREEN),
+        );
+        map.set(
+            starting_point + Point::new(0, -1),
+            to_cp437('#'),
+            RGB::named(GREEN),
+        );
+        frames.record(&map, "Starting Seed".to_string());
+
+        // Keep adding particles until this much of the map is open
+        let target = param("open", 1.0f32 / 3.0).min(0.9);
+        while (map.tiles.iter().filter(|t| t.0 == to_cp437('#')).count() as f32)
+            < map.tiles.len() as f32 * target
+        {
+            let mut digger = Point::new(
+                rng.roll_dice(1, map.width as i32 - 3) + 1,
+                rng.roll_dice(1, map.height as i32 - 3) + 1,
+            );
+            let mut prev = digger;
+            let mut digger_idx = map.idx(digger.x, digger.y);
+            while map.tiles[digger_idx].0 == to_cp437('.') {
+                prev = digger;
+                let stagger_direction = rng.roll_dice(1, 4);
+                match stagger_direction {
+                    1 => {
+                        if digger.x > 2 {
+                            digger.x -= 1;
+                        }
+                    }
+                    2 => {
+                        if digger.x < map.width as i32 - 2 {
+                            digger.x += 1;
+                        }
+                    }
+                    3 => {
+                        if digger.y > 2 {
+                            digger.y -= 1;
+                        }
+                    }
+                    _ => {
+                        if digger.y < map.height as i32 - 2 {
+                            digger.y += 1;
+                        }
+                    }
+                }
+                digger_idx = map.idx(digger.x, digger.y);
+            }
+            map.set(prev, to_cp437('#'), RGB::named(GREEN));
+            frames.record(&map, "Iteration".to_string());
+        }
+    }
+}
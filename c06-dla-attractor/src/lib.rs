@@ -0,0 +1,83 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "DLA with an Attractor",
+        "Particles fly straight at the middle and stick where they hit",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        let starting_point = Point::new(map.width / 2, map.height / 2);
+        map.set(starting_point, to_cp437('#'), RGB::named(RED));
+        map.set(
+            starting_point + Point::new(1, 0),
+            to_cp437('#'),
+            RGB::named(RED),
+        );
+        map.set(
+            starting_point + Point::new(-1, 0),
+            to_cp437('#'),
+            RGB::named(RED),
+        );
+        map.set(
+            starting_point + Point::new(0, 1),
+            to_cp437('#'),
+            RGB::named(RED),
+        );
+        map.set(
+            starting_point + Point::new(0, -1),
+            to_cp437('#'),
+            RGB::named(RED),
+        );
+        frames.record(&map, "Starting Seed".to_string());
+
+        // Keep adding particles until this much of the map is open
+        let target = param("open", 1.0f32 / 3.0).min(0.9);
+        while (map.tiles.iter().filter(|t| t.0 == to_cp437('#')).count() as f32)
+            < map.tiles.len() as f32 * target
+        {
+            map.tiles
+                .iter_mut()
+                .filter(|t| t.1 == RGB::named(RED))
+                .for_each(|t| t.1 = RGB::named(GREEN));
+
+            let mut digger = Point::new(
+                rng.roll_dice(1, map.width as i32 - 3) + 1,
+                rng.roll_dice(1, map.height as i32 - 3) + 1,
+            );
+            let mut digger_idx = map.idx(digger.x, digger.y);
+            let mut prev = digger;
+
+            let mut path = line2d(
+                LineAlg::Bresenham,
+                digger,
+                Point::new(map.width / 2, map.height / 2),
+            );
+
+            while map.tiles[digger_idx].0 == to_cp437('.') && !path.is_empty() {
+                prev = digger;
+                digger = path[0];
+                digger_idx = map.idx(digger.x, digger.y);
+                path.remove(0);
+            }
+            map.set(prev, to_cp437('#'), RGB::named(RED));
+            frames.record(&map, "Iteration".to_string());
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Symmetrical DLA",
+        "Aggregation mirrored across the middle of the map",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        let starting_point = Point::new(map.width / 2, map.height / 2);
+        map.set(starting_point, to_cp437('#'), RGB::named(GREEN));
+        map.set(
+            starting_point + Point::new(1, 0),
+            to_cp437('#'),
+            RGB::named(GREEN),
+        );
+        map.set(
+            starting_point + Point::new(-1, 0),
+            to_cp437('#'),
+            RGB::named(GREEN),
+        );
+        map.set(
+            starting_point + Point::new(0, 1),
+            to_cp437('#'),
+            RGB::named(GREEN),
+        );
+        map.set(
+            starting_point + Point::new(0, -1),
+            to_cp437('#'),
+            RGB::named(GREEN),
+        );
+        frames.record(&map, "Starting Seed".to_string());
+
+        // Keep adding particles until this much of the map is open
+        let target = param("open", 1.0f32 / 3.0).min(0.9);
+        while (map.tiles.iter().filter(|t| t.0 == to_cp437('#')).count() as f32)
+            < map.tiles.len() as f32 * target
+        {
+            let mut digger = Point::new(
+                rng.roll_dice(1, map.width as i32 - 3) + 1,
+                rng.roll_dice(1, map.height as i32 - 3) + 1,
+            );
+            let mut digger_idx = map.idx(digger.x, digger.y);
+            let mut prev = digger;
+
+            let mut path = line2d(
+                LineAlg::Bresenham,
+                digger,
+                Point::new(map.width / 2, map.height / 2),
+            );
+
+            while map.tiles[digger_idx].0 == to_cp437('.') && !path.is_empty() {
+                prev = digger;
+                digger = path[0];
+                digger_idx = map.idx(digger.x, digger.y);
+                path.remove(0);
+            }
+
+            let center_x = map.width as i32 / 2;
+            if center_x == prev.x {
+                map.set(prev, to_cp437('#'), RGB::named(GREEN));
+            } else {
+                let dist_x = i32::abs(center_x - prev.x);
+                let p1 = Point::new(center_x - dist_x, prev.y);
+                let p2 = Point::new(center_x + dist_x, prev.y);
+                map.set(p1, to_cp437('#'), RGB::named(GREEN));
+                map.set(p2, to_cp437('#'), RGB::named(GREEN));
+            }
+            frames.record(&map, "Iteration".to_string());
+        }
+    }
+}
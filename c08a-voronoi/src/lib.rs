@@ -0,0 +1,122 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Voronoi Regions",
+        "Seeds claim their nearest tiles, with walls along the borders",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+
+        let mut map = Map::new();
+
+        // Seed it
+        let mut seeds = Vec::new();
+        for _ in 0..16 {
+            seeds.push(Point::new(
+                rng.range(1, map.width - 1),
+                rng.range(1, map.height - 1),
+            ));
+        }
+
+        for (i, s) in seeds.iter().enumerate() {
+            map.set(*s, to_cp437('*'), iteration_color(i))
+        }
+        frames.record(&map, "Initial Seeds".to_string());
+
+        // Allocate tiles
+        map = Map::new();
+        let mut membership = vec![0; map.tiles.len()];
+        for (i, m) in membership.iter_mut().enumerate() {
+            let my_pos = map.point(i);
+            let closest = seeds
+                .iter()
+                .enumerate()
+                .map(|(i, pos)| (i, DistanceAlg::Pythagoras.distance2d(my_pos, *pos)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .unwrap()
+                .0;
+            *m = closest;
+        }
+        for (i, m) in membership.iter().enumerate() {
+            let my_pos = map.point(i);
+            map.set(my_pos, to_cp437('#'), iteration_color(*m));
+        }
+        frames.record(&map, "Closest Membership (Pythagoras)".to_string());
+        let membership_py = membership.clone();
+
+        // Allocate Tiles - this time with a different heuristic
+        map = Map::new();
+        let mut membership = vec![0; map.tiles.len()];
+        for (i, m) in membership.iter_mut().enumerate() {
+            let my_pos = map.point(i);
+            let closest = seeds
+                .iter()
+                .enumerate()
+                .map(|(i, pos)| (i, DistanceAlg::Manhattan.distance2d(my_pos, *pos)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .unwrap()
+                .0;
+            *m = closest;
+        }
+        for (i, m) in membership.iter().enumerate() {
+            let my_pos = map.point(i);
+            map.set(my_pos, to_cp437('#'), iteration_color(*m));
+        }
+        frames.record(&map, "Closest Membership (Manhattan)".to_string());
+
+        // Allocate Tiles - this time with a different heuristic
+        map = Map::new();
+        let mut membership = vec![0; map.tiles.len()];
+        for (i, m) in membership.iter_mut().enumerate() {
+            let my_pos = map.point(i);
+            let closest = seeds
+                .iter()
+                .enumerate()
+                .map(|(i, pos)| (i, DistanceAlg::Chebyshev.distance2d(my_pos, *pos)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .unwrap()
+                .0;
+            *m = closest;
+        }
+        for (i, m) in membership.iter().enumerate() {
+            let my_pos = map.point(i);
+            map.set(my_pos, to_cp437('#'), iteration_color(*m));
+        }
+        frames.record(&map, "Closest Membership (Chebyshev)".to_string());
+
+        // Find edges
+        map = Map::new();
+        for i in 0..map.tiles.len() {
+            let my_pos = map.point(i);
+            if my_pos.x == 0
+                || my_pos.x == map.width as i32 - 1
+                || my_pos.y == 0
+                || my_pos.y == map.height as i32 - 1
+            {
+                map.set(my_pos, to_cp437('.'), RGB::named(DARK_GRAY));
+            } else {
+                if membership_py[i] != membership_py[i + 1]
+                    || membership_py[i] != membership_py[i + map.width]
+                {
+                    map.set(my_pos, to_cp437('#'), RGB::named(YELLOW));
+                }
+            }
+        }
+        frames.record(&map, "Voronoi Boundary Walls".to_string());
+    }
+}
@@ -0,0 +1,308 @@
+use backend::*;
+
+pub struct RoomBuilder {
+    rects: Vec<Rect>,
+    rooms: Vec<Rect>,
+}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            rects: Vec::new(),
+            rooms: Vec::new(),
+        })
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Combining Generators",
+        "BSP rooms on one side, caves on the other, and a prefab fortress",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        // Make the first room
+        let mut map = Map::new();
+        self.rects.push(Rect::with_size(
+            2,
+            2,
+            map.width as i32 - 5,
+            map.height as i32 - 5,
+        ));
+
+        // Divide
+        let first_room = self.rects[0];
+        self.add_subrects(first_room);
+
+        for room in self.rects.iter() {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), RGB::named(YELLOW));
+            });
+        }
+
+        let mut rng = new_rng();
+        let mut map = Map::new();
+        let mut n_rooms = 0;
+        while n_rooms < 240 {
+            let rect = self.get_random_rect(&mut rng);
+            let candidate = self.get_random_sub_rect(rect, &mut rng);
+            if self.is_possible(candidate, &map) {
+                candidate.for_each(|p| {
+                    map.set(p, to_cp437('#'), RGB::named(YELLOW));
+                });
+                self.rooms.push(candidate);
+                self.add_subrects(rect);
+            }
+            n_rooms += 1;
+        }
+
+        // Sort it
+        self.rooms.sort_by_key(|a| a.x1);
+        map.clear_default();
+        for room in self.rooms.iter() {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), RGB::named(YELLOW));
+            });
+        }
+
+        // Corridors
+        for (i, room) in self.rooms.iter().enumerate().skip(1) {
+            let prev = self.rooms[i - 1].center();
+            let new = room.center();
+
+            if rng.range(0, 2) == 1 {
+                apply_horizontal_tunnel(prev.x, new.x, prev.y, &mut map);
+                apply_vertical_tunnel(prev.y, new.y, new.x, &mut map);
+            } else {
+                apply_vertical_tunnel(prev.y, new.y, prev.x, &mut map);
+                apply_horizontal_tunnel(prev.x, new.x, new.y, &mut map);
+            }
+        }
+        frames.record(&map, "Make some sub-division rooms".to_string());
+
+        // Store it
+        let bsp = map.clone();
+
+        // Make a CA map
+        map.tiles.iter_mut().for_each(|(t, c)| {
+            let roll = rng.range(0, 100);
+            if roll < 55 {
+                *t = to_cp437('.');
+                *c = RGB::named(DARK_GRAY);
+            } else {
+                *t = to_cp437('#');
+                *c = RGB::named(YELLOW);
+            }
+        });
+
+        for _ in 0..4 {
+            iterate(&mut map);
+        }
+        frames.record(&map, "Make a Cellular Automata Map".to_string());
+        let ca = map.clone();
+
+        // Combine the two
+        map = Map::new();
+        let center_x = map.width as i32 / 2;
+        for y in 0..map.height as i32 {
+            for x in 0..center_x {
+                let idx = map.idx(x, y);
+                map.tiles[idx] = bsp.tiles[idx];
+            }
+        }
+        for y in 0..map.height as i32 {
+            for x in center_x..map.width as i32 {
+                let idx = map.idx(x, y);
+                map.tiles[idx] = ca.tiles[idx];
+            }
+        }
+        frames.record(&map, "Why Not Both?".to_string());
+
+        let fortress = PrefabSection {
+            template: PREFAB,
+            width: 11,
+            height: 50,
+            placement: (HorizontalPlacement::Center, VerticalPlacement::Top),
+            // The fortress has always sat a column right of center
+            offset: Point::new(1, 0),
+        };
+        apply_sectional(&mut map, &fortress, RGB::named(YELLOW));
+        frames.record(&map, "Just Add Prefab".to_string());
+    }
+}
+
+impl RoomBuilder {
+    fn add_subrects(&mut self, rect: Rect) {
+        self.rects.extend_from_slice(&subdivide(rect));
+    }
+
+    fn get_random_rect(&mut self, rng: &mut RandomNumberGenerator) -> Rect {
+        if self.rects.len() == 1 {
+            return self.rects[0];
+        }
+        let idx = (rng.roll_dice(1, self.rects.len() as i32) - 1) as usize;
+        self.rects[idx]
+    }
+
+    fn get_random_sub_rect(&self, rect: Rect, rng: &mut RandomNumberGenerator) -> Rect {
+        let mut result = rect;
+        let rect_width = i32::abs(rect.x1 - rect.x2);
+        let rect_height = i32::abs(rect.y1 - rect.y2);
+
+        let w = i32::max(3, rng.roll_dice(1, i32::min(rect_width, 10)) - 1) + 1;
+        let h = i32::max(3, rng.roll_dice(1, i32::min(rect_height, 10)) - 1) + 1;
+
+        result.x1 += rng.roll_dice(1, 6) - 1;
+        result.y1 += rng.roll_dice(1, 6) - 1;
+        result.x2 = result.x1 + w;
+        result.y2 = result.y1 + h;
+
+        result
+    }
+
+    fn is_possible(&self, rect: Rect, map: &Map) -> bool {
+        let mut expanded = rect;
+        expanded.x1 -= 2;
+        expanded.x2 += 2;
+        expanded.y1 -= 2;
+        expanded.y2 += 2;
+
+        let mut can_build = true;
+
+        for y in expanded.y1..=expanded.y2 {
+            for x in expanded.x1..=expanded.x2 {
+                if x > map.width as i32 - 2 {
+                    can_build = false;
+                }
+                if y > map.height as i32 - 2 {
+                    can_build = false;
+                }
+                if x < 1 {
+                    can_build = false;
+                }
+                if y < 1 {
+                    can_build = false;
+                }
+                if can_build {
+                    if let Some(idx) = map.try_idx(Point::new(x, y)) {
+                        if map.tiles[idx].0 != to_cp437('.') {
+                            can_build = false;
+                        }
+                    } else {
+                        can_build = false;
+                    }
+                }
+            }
+        }
+
+        can_build
+    }
+}
+
+fn apply_horizontal_tunnel(x1: i32, x2: i32, y: i32, map: &mut Map) {
+    use std::cmp::{max, min};
+    for x in min(x1, x2)..=max(x1, x2) {
+        if let Some(idx) = map.try_idx(Point::new(x, y)) {
+            map.tiles[idx] = (to_cp437('#'), RGB::named(YELLOW));
+        }
+    }
+}
+
+fn apply_vertical_tunnel(y1: i32, y2: i32, x: i32, map: &mut Map) {
+    use std::cmp::{max, min};
+    for y in min(y1, y2)..=max(y1, y2) {
+        if let Some(idx) = map.try_idx(Point::new(x, y)) {
+            map.tiles[idx] = (to_cp437('#'), RGB::named(YELLOW));
+        }
+    }
+}
+
+fn count_neighbors(map: &Map, x: usize, y: usize) -> usize {
+    let mut n = 0;
+    for ty in -1..=1 {
+        for tx in -1..=1 {
+            if !(ty == 0 && tx == 0) {
+                if let Some(idx) = map.try_idx(Point::new(x as i32 + tx, y as i32 + ty)) {
+                    if map.tiles[idx].0 == to_cp437('.') {
+                        n += 1;
+                    }
+                }
+            }
+        }
+    }
+    n
+}
+
+fn iterate(map: &mut Map) {
+    let map_copy = map.clone();
+    for y in 1..map.height - 1 {
+        for x in 1..map.width - 1 {
+            let neighbors = count_neighbors(&map_copy, x, y);
+            if neighbors == 0 {
+                map.set(Point::new(x, y), to_cp437('.'), RGB::named(DARK_GRAY));
+            } else if neighbors < 5 {
+                map.set(Point::new(x, y), to_cp437('#'), RGB::named(YELLOW));
+            } else {
+                map.set(Point::new(x, y), to_cp437('.'), RGB::named(DARK_GRAY));
+            }
+        }
+    }
+}
+
+const PREFAB: &str = "
+.#####.....
+.....#.....
+.#...#.....
+.#.###.....
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#######.
+.#.#.....#.
+.#.#.....#.
+.#.#.....#.
+.#.......#.
+.#.#######.
+...........
+...........
+.#.#######.
+.#.......#.
+.#.#.....#.
+.#.#.....#.
+.#.#.....#.
+.#.#######.
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.#.......
+.#.###.....
+.#...#.....
+.#...#.....
+.####......
+";
@@ -0,0 +1,71 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Noise Overworld",
+        "Noise shaded into sea, plains and mountains, seed after seed",
+        || RoomBuilder::new(),
+    );
+}
+
+fn noise_map(
+    seed: u64,
+    octaves: i32,
+    gain: f32,
+    lacunarity: f32,
+    freq: f32,
+    frames: &mut dyn SnapshotRecorder,
+) {
+    let mut map = Map::new();
+    let mut noise = FastNoise::seeded(seed);
+    noise.set_noise_type(NoiseType::SimplexFractal);
+    noise.set_fractal_type(FractalType::FBM);
+    noise.set_fractal_octaves(octaves);
+    noise.set_fractal_gain(gain);
+    noise.set_fractal_lacunarity(lacunarity);
+    noise.set_frequency(freq);
+
+    for y in 0..map.height {
+        for x in 0..map.width {
+            let n = noise.get_noise(x as f32 / 100.0, y as f32 / 50.0);
+            if n < 0.0 {
+                map.set(
+                    Point::new(x, y),
+                    to_cp437('~'),
+                    RGB::from_f32(0.0, 0.0, n + 0.75),
+                );
+            } else if n < 0.5 {
+                map.set(
+                    Point::new(x, y),
+                    to_cp437(';'),
+                    RGB::from_f32(0.0, n + 0.25, 0.0),
+                );
+            } else {
+                map.set(Point::new(x, y), to_cp437('^'), RGB::from_f32(n, n, n));
+            }
+        }
+    }
+    frames.record(&map, format!("Seed: {}", seed));
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let octaves = param("octaves", 10);
+        let gain = param("gain", 0.1);
+        let lacunarity = param("lacunarity", 5.0);
+        let freq = param("frequency", 2.0);
+        for seed in 0..param("worlds", 50) {
+            noise_map(seed, octaves, gain, lacunarity, freq, frames);
+        }
+    }
+}
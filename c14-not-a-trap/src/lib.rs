@@ -0,0 +1,160 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Not A Trap",
+        "A prefab vault dropped into the first room it fits",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rooms = Vec::<(Rect, usize)>::new();
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        let mut room_counter = 0;
+        for _ in 0..param("rooms", 20) {
+            let room = Rect::with_size(
+                rng.range(1, map.width as i32 - 10),
+                rng.range(1, map.height as i32 - 10),
+                rng.range(2, 10),
+                rng.range(2, 10),
+            );
+            let mut overlap = false;
+            for (r, _) in rooms.iter() {
+                let mut r_grow = *r;
+                r_grow.x1 -= 1;
+                r_grow.y1 -= 1;
+                r_grow.x2 += 1;
+                r_grow.y2 += 1;
+                if r_grow.intersect(&room) {
+                    overlap = true;
+                }
+            }
+
+            if !overlap {
+                room.for_each(|p| {
+                    map.set(p, to_cp437('#'), iteration_color(room_counter));
+                });
+                rooms.push((room, room_counter));
+                room_counter += 1;
+            } else {
+                let mut discard = map.clone();
+                room.for_each(|p| {
+                    discard.set(p, to_cp437('!'), iteration_color(666));
+                });
+            }
+        }
+
+        // Sort it
+        rooms.sort_by_key(|a| a.0.x1);
+        map.clear_default();
+        for (room, iteration) in rooms.iter() {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), iteration_color(*iteration));
+            });
+        }
+
+        // Corridors
+        for (i, room) in rooms.iter().enumerate().skip(1) {
+            let prev = rooms[i - 1].0.center();
+            let new = room.0.center();
+
+            if rng.range(0, 2) == 1 {
+                apply_horizontal_tunnel(prev.x, new.x, prev.y, &mut map);
+                apply_vertical_tunnel(prev.y, new.y, new.x, &mut map);
+            } else {
+                apply_vertical_tunnel(prev.y, new.y, prev.x, &mut map);
+                apply_horizontal_tunnel(prev.x, new.x, new.y, &mut map);
+            }
+        }
+
+        // Made the map
+        frames.record(&map, "Basic Rooms Map".to_string());
+        let room_map = map.clone();
+
+        // Display the prefab
+        map = Map::new();
+        apply_prefab(&mut map, NOT_TRAP, 6, Point::new(36, 20), RGB::named(GREEN));
+        frames.record(&map, "This Prefab is Definitely Not A Trap".to_string());
+
+        // Place the prefab
+        map = room_map;
+        if !rooms.iter().any(|(r, _)| r.width() > 5 && r.height() > 5) {
+            frames.record(&map, "No Room is Big Enough for the Prefab".to_string());
+            return;
+        }
+        let trap_room = loop {
+            let r = rng.random_slice_entry(&rooms).unwrap().0;
+            if r.width() > 5 && r.height() > 5 {
+                let base = r.center() - Point::new(3, 2);
+                apply_prefab(&mut map, NOT_TRAP, 6, base, RGB::named(GREEN));
+                break r;
+            }
+        };
+        frames.record(&map, "Place Prefab in Room that Fits".to_string());
+
+        // Furnish everything else, apart from where we start
+        let mut other_rooms: Vec<Rect> = rooms
+            .iter()
+            .map(|(r, _)| *r)
+            .filter(|r| *r != trap_room)
+            .collect();
+        if other_rooms.is_empty() {
+            frames.record(&map, "Nowhere to Start Outside the Trap Room".to_string());
+            return;
+        }
+        let start = other_rooms.remove(0).center();
+        apply_room_prefabs(
+            &mut map,
+            &other_rooms,
+            ROOM_PREFABS,
+            RGB::named(YELLOW),
+            &mut rng,
+        );
+        frames.record(&map, "Furnish the Other Rooms".to_string());
+
+        // Exit as far from the start as possible, so the path to it crosses
+        // the map
+        let start_idx = map.idx(start.x, start.y);
+        let exit = find_placement(&map, &Placement::FarthestFrom(start_idx))
+            .expect("The start is open floor");
+        map.set(start, to_cp437('@'), RGB::named(GOLD));
+        map.set(map.point(exit), to_cp437('>'), RGB::named(MAGENTA));
+
+        // Is it fair?
+        let violations = check_fairness(&map, start_idx, Some(exit));
+        for v in violations.iter() {
+            let mut highlight = map.clone();
+            let idx = map.idx(v.position().x, v.position().y);
+            highlight.tiles[idx].1 = RGB::named(MAGENTA);
+            frames.record(&highlight, v.to_string());
+        }
+        if violations.is_empty() {
+            frames.record(
+                &map,
+                "Every Treasure and the Exit are Reachable Without a Trap".to_string(),
+            );
+        }
+    }
+}
+
+const NOT_TRAP: &str = "
+......
+.^^^^.
+.^$$^.
+.^^^^.
+......
+";
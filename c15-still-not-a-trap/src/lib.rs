@@ -0,0 +1,93 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Still Not A Trap",
+        "Prefabs placed in a cave and checked for fairness",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut map = Map::new();
+        let mut rng = new_rng();
+        random_fill(&mut map, param("fill", 55), &mut rng);
+        for _ in 0..param("iterations", 10) {
+            cellular_iterate(&mut map);
+        }
+
+        frames.record(&map, "Cellular Automata Map".to_string());
+
+        // Give up if the caves never open up enough for it
+        let mut placed = false;
+        for _ in 0..1000 {
+            let base = Point::new(rng.range(1, map.width - 10), rng.range(1, map.height - 10));
+            let mut can_build = true;
+            let target = Rect::with_size(base.x, base.y, 6, 5);
+            target.for_each(|p| {
+                let idx = map.idx(p.x, p.y);
+                if map.tiles[idx].0 != to_cp437('#') {
+                    can_build = false;
+                }
+            });
+            if can_build {
+                apply_prefab(&mut map, NOT_TRAP, 6, base, RGB::named(GREEN));
+                placed = true;
+                break;
+            }
+        }
+
+        if placed {
+            frames.record(&map, "Found a place for the prefab".to_string());
+        } else {
+            frames.record(&map, "Nowhere big enough for the prefab".to_string());
+        }
+
+        // Find a central starting point
+        let start = match find_placement(&map, &Placement::Nearest(Anchor::Center)) {
+            Ok(start) => start,
+            Err(_) => {
+                frames.record(&map, "No Open Floor, So No Start".to_string());
+                return;
+            }
+        };
+        let exit =
+            find_placement(&map, &Placement::FarthestFrom(start)).expect("The start is open floor");
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+        map.set(map.point(exit), to_cp437('>'), RGB::named(MAGENTA));
+
+        // Is it fair?
+        let violations = check_fairness(&map, start, Some(exit));
+        for v in violations.iter() {
+            let mut highlight = map.clone();
+            let idx = map.idx(v.position().x, v.position().y);
+            highlight.tiles[idx].1 = RGB::named(MAGENTA);
+            frames.record(&highlight, v.to_string());
+        }
+        if violations.is_empty() {
+            frames.record(
+                &map,
+                "Every Treasure and the Exit are Reachable Without a Trap".to_string(),
+            );
+        }
+    }
+}
+
+const NOT_TRAP: &str = "
+......
+.^^^^.
+.^$$^.
+.^^^^.
+......
+";
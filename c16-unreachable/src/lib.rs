@@ -0,0 +1,56 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Unreachable Areas",
+        "A Dijkstra map from the middle finds what can't be reached",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut map = Map::new();
+        let mut rng = new_rng();
+        random_fill(&mut map, param("fill", 55), &mut rng);
+        for _ in 0..param("iterations", 10) {
+            cellular_iterate(&mut map);
+        }
+
+        frames.record(&map, "Cellular Automata Map".to_string());
+
+        // Find a central starting point
+        let start = match find_placement(&map, &Placement::Nearest(Anchor::Center)) {
+            Ok(start) => start,
+            Err(_) => {
+                frames.record(&map, "No Open Floor, So No Start".to_string());
+                return;
+            }
+        };
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+        frames.record(&map, "Central Open Point".to_string());
+
+        // Build a Dijkstra Map
+        let dijkstra = DijkstraMap::new(map.width, map.height, &[start], &map, DIJKSTRA_DEPTH);
+        let unreachable = dijkstra
+            .map
+            .iter()
+            .enumerate()
+            .filter(|(i, d)| map.tiles[*i].0 == to_cp437('#') && **d == f32::MAX)
+            .map(|(i, _)| (i, to_cp437('#'), RGB::named(RED)))
+            .collect();
+        map.overlays.push(Overlay::heatmap(&dijkstra.map));
+        map.overlays.push(Overlay::Markers(unreachable));
+        frames.record(&map, "Mark Reachable/Unreachable".to_string());
+    }
+}
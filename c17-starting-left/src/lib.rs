@@ -0,0 +1,57 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Starting on the Left",
+        "Culls the unreachable, then starts nearest the left edge",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut map = Map::new();
+        let mut rng = new_rng();
+        random_fill(&mut map, param("fill", 55), &mut rng);
+        for _ in 0..param("iterations", 10) {
+            cellular_iterate(&mut map);
+        }
+
+        // Find a central starting point
+        let start = match find_placement(&map, &Placement::Nearest(Anchor::Center)) {
+            Ok(start) => start,
+            Err(_) => {
+                frames.record(&map, "No Open Floor, So No Start".to_string());
+                return;
+            }
+        };
+
+        // Build a Dijkstra Map
+        let dijkstra = DijkstraMap::new(map.width, map.height, &[start], &map, DIJKSTRA_DEPTH);
+        for (i, d) in dijkstra.map.iter().enumerate() {
+            if map.tiles[i].0 == to_cp437('#') && *d == f32::MAX {
+                map.set(map.point(i), to_cp437('.'), RGB::named(DARK_GRAY))
+            }
+        }
+        frames.record(&map, " Start with a culled map ".to_string());
+
+        // Preferred start
+        let desired_start = Anchor::Left;
+        let start =
+            find_placement(&map, &Placement::Nearest(desired_start)).expect("No open floor");
+        map.set(desired_start.point(&map), to_cp437('!'), RGB::named(RED));
+        frames.record(&map, " Desired Start ".to_string());
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+        frames.record(&map, " Actual Start ".to_string());
+    }
+}
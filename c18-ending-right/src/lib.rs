@@ -0,0 +1,67 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Ending on the Right",
+        "Puts the exit nearest the right edge",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut map = Map::new();
+        let mut rng = new_rng();
+        random_fill(&mut map, param("fill", 55), &mut rng);
+        for _ in 0..param("iterations", 10) {
+            cellular_iterate(&mut map);
+        }
+
+        // Find a central starting point
+        let start = match find_placement(&map, &Placement::Nearest(Anchor::Center)) {
+            Ok(start) => start,
+            Err(_) => {
+                frames.record(&map, "No Open Floor, So No Start".to_string());
+                return;
+            }
+        };
+
+        // Build a Dijkstra Map
+        let dijkstra = DijkstraMap::new(map.width, map.height, &[start], &map, DIJKSTRA_DEPTH);
+        for (i, d) in dijkstra.map.iter().enumerate() {
+            if map.tiles[i].0 == to_cp437('#') && *d == f32::MAX {
+                map.set(map.point(i), to_cp437('.'), RGB::named(DARK_GRAY))
+            }
+        }
+
+        // Preferred start
+        let desired_start = Anchor::Left;
+        let start =
+            find_placement(&map, &Placement::Nearest(desired_start)).expect("No open floor");
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+
+        // Preferred end
+        let desired_end = Anchor::Right;
+        let end = find_placement(&map, &Placement::Nearest(desired_end)).expect("No open floor");
+
+        let path = a_star_search(start, end, &map);
+        for p in path.steps.iter() {
+            map.set(map.point(*p), to_cp437('*'), RGB::named(PURPLE));
+        }
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+        map.set(desired_end.point(&map), to_cp437('!'), RGB::named(RED));
+        map.set(map.point(end), to_cp437('>'), RGB::named(GOLD));
+
+        frames.record(&map, " Exit by direction ".to_string());
+    }
+}
@@ -0,0 +1,78 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Ending by Dijkstra",
+        "Exits at the least accessible tile, and finds the map diameter",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut map = Map::new();
+        let mut rng = new_rng();
+        random_fill(&mut map, param("fill", 55), &mut rng);
+        for _ in 0..param("iterations", 10) {
+            cellular_iterate(&mut map);
+        }
+
+        // Find a central starting point
+        let start = match find_placement(&map, &Placement::Nearest(Anchor::Center)) {
+            Ok(start) => start,
+            Err(_) => {
+                frames.record(&map, "No Open Floor, So No Start".to_string());
+                return;
+            }
+        };
+
+        // Build a Dijkstra Map
+        let dijkstra = DijkstraMap::new(map.width, map.height, &[start], &map, DIJKSTRA_DEPTH);
+        for (i, d) in dijkstra.map.iter().enumerate() {
+            if map.tiles[i].0 == to_cp437('#') && *d == f32::MAX {
+                map.set(map.point(i), to_cp437('.'), RGB::named(DARK_GRAY))
+            }
+        }
+
+        let culled = map.clone();
+
+        // Preferred start
+        let desired_start = Anchor::Center;
+        let start =
+            find_placement(&map, &Placement::Nearest(desired_start)).expect("No open floor");
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+
+        // Preferred end
+        let end = find_placement(&map, &Placement::FarthestFrom(start)).expect("No open floor");
+
+        let path = a_star_search(start, end, &map);
+        for p in path.steps.iter() {
+            map.set(map.point(*p), to_cp437('*'), RGB::named(PURPLE));
+        }
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+        map.set(map.point(end), to_cp437('>'), RGB::named(GOLD));
+
+        frames.record(&map, " Least Accessible Exit ".to_string());
+
+        // Longest possible walk, from a double Dijkstra sweep
+        let mut map = culled;
+        let (start, end) = map_diameter(&map).expect("No open floor");
+        let path = a_star_search(start, end, &map);
+        for p in path.steps.iter() {
+            map.set(map.point(*p), to_cp437('*'), RGB::named(PURPLE));
+        }
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+        map.set(map.point(end), to_cp437('>'), RGB::named(GOLD));
+        frames.record(&map, " Map Diameter ".to_string());
+    }
+}
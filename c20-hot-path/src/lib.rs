@@ -0,0 +1,81 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "The Hot Path",
+        "The route to the exit, and everything near it",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut map = Map::new();
+        let mut rng = new_rng();
+        random_fill(&mut map, param("fill", 55), &mut rng);
+        for _ in 0..param("iterations", 10) {
+            cellular_iterate(&mut map);
+        }
+
+        // Find a central starting point
+        let start = match find_placement(&map, &Placement::Nearest(Anchor::Center)) {
+            Ok(start) => start,
+            Err(_) => {
+                frames.record(&map, "No Open Floor, So No Start".to_string());
+                return;
+            }
+        };
+
+        // Build a Dijkstra Map
+        let dijkstra = DijkstraMap::new(map.width, map.height, &[start], &map, DIJKSTRA_DEPTH);
+        for (i, d) in dijkstra.map.iter().enumerate() {
+            if map.tiles[i].0 == to_cp437('#') && *d == f32::MAX {
+                map.set(map.point(i), to_cp437('.'), RGB::named(DARK_GRAY))
+            }
+        }
+
+        // Preferred start
+        let desired_start = Anchor::Left;
+        let start =
+            find_placement(&map, &Placement::Nearest(desired_start)).expect("No open floor");
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+
+        // Preferred end
+        let desired_end = Anchor::Right;
+        let end = find_placement(&map, &Placement::Nearest(desired_end)).expect("No open floor");
+
+        let path = a_star_search(start, end, &map);
+        map.overlays.push(Overlay::path(&map, &path.steps));
+        map.set(desired_end.point(&map), to_cp437('!'), RGB::named(RED));
+        map.set(map.point(end), to_cp437('>'), RGB::named(GOLD));
+
+        frames.record(&map, " Path To Exit ".to_string());
+
+        let hot_path = DijkstraMap::new(map.width, map.height, &path.steps, &map, DIJKSTRA_DEPTH);
+        for (i, t) in map.tiles.iter_mut().enumerate() {
+            if hot_path.map[i] < 10.0 {
+                t.1 = RGB::named(GOLD);
+            }
+        }
+        frames.record(&map, " Hot Path ".to_string());
+
+        let mut mc = map.clone();
+        for (i, t) in mc.tiles.iter_mut().enumerate() {
+            if hot_path.map[i] >= 15.0 {
+                t.1 = RGB::named(DARK_GRAY);
+                t.0 = to_cp437('.');
+            }
+        }
+        frames.record(&mc, " Railroad by Culling ".to_string());
+    }
+}
@@ -0,0 +1,181 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Hot Rooms",
+        "Rooms on the critical path, and how far side branches wander",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rooms = Vec::<(Rect, usize)>::new();
+        let mut rng = new_rng();
+        let mut map = Map::new();
+        map.movement = MovementCosts {
+            diagonals: true,
+            ..MovementCosts::dungeon()
+        };
+
+        let mut room_counter = 0;
+        for _ in 0..param("rooms", 50) {
+            let room = Rect::with_size(
+                rng.range(1, map.width as i32 - 10),
+                rng.range(1, map.height as i32 - 10),
+                rng.range(2, 10),
+                rng.range(2, 10),
+            );
+            let mut overlap = false;
+            for (r, _) in rooms.iter() {
+                let mut r_grow = *r;
+                r_grow.x1 -= 1;
+                r_grow.y1 -= 1;
+                r_grow.x2 += 1;
+                r_grow.y2 += 1;
+                if r_grow.intersect(&room) {
+                    overlap = true;
+                }
+            }
+
+            if !overlap {
+                room.for_each(|p| {
+                    map.set(p, to_cp437('#'), iteration_color(room_counter));
+                });
+                rooms.push((room, room_counter));
+                room_counter += 1;
+            } else {
+                let mut discard = map.clone();
+                room.for_each(|p| {
+                    discard.set(p, to_cp437('!'), iteration_color(666));
+                });
+            }
+        }
+
+        // Sort it
+        rooms.sort_by_key(|a| a.0.x1);
+        map.clear_default();
+        for (room, iteration) in rooms.iter() {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), iteration_color(*iteration));
+            });
+        }
+
+        // Corridors
+        for (i, room) in rooms.iter().enumerate().skip(1) {
+            let prev = rooms[i - 1].0.center();
+            let new = room.0.center();
+
+            if rng.range(0, 2) == 1 {
+                apply_horizontal_tunnel(prev.x, new.x, prev.y, &mut map);
+                apply_vertical_tunnel(prev.y, new.y, new.x, &mut map);
+            } else {
+                apply_vertical_tunnel(prev.y, new.y, prev.x, &mut map);
+                apply_horizontal_tunnel(prev.x, new.x, new.y, &mut map);
+            }
+        }
+
+        // Flood some rooms and fill others with rubble, so they cost more to cross
+        for (room, _) in rooms.iter() {
+            let terrain = match rng.range(0, 4) {
+                0 => Some((to_cp437('~'), RGB::named(BLUE))),
+                1 => Some((to_cp437(':'), RGB::named(SADDLEBROWN))),
+                _ => None,
+            };
+            if let Some((glyph, color)) = terrain {
+                room.for_each(|p| map.set(p, glyph, color));
+            }
+        }
+
+        // Start/end
+        let (start, end) = match (
+            find_placement(&map, &Placement::Nearest(Anchor::Left)),
+            find_placement(&map, &Placement::Nearest(Anchor::Right)),
+        ) {
+            (Ok(start), Ok(end)) => (start, end),
+            _ => {
+                frames.record(&map, "No Rooms, So No Start or End".to_string());
+                return;
+            }
+        };
+
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+        map.set(map.point(end), to_cp437('>'), RGB::named(GOLD));
+        map.tiles.iter_mut().for_each(|t| {
+            if t.0 == to_cp437('#') {
+                t.1 = RGB::named(GREEN);
+            }
+        });
+        frames.record(&map, "Rooms with Start/End".to_string());
+
+        // Counting every step the same, the route wades straight through
+        let mut flat = map.clone();
+        flat.movement = MovementCosts::default();
+        let shortest = a_star_search(start, end, &flat);
+        let mut route = map.clone();
+        route.overlays.push(Overlay::path(&route, &shortest.steps));
+        frames.record(&route, "Shortest Route, Ignoring Terrain".to_string());
+
+        // With terrain costs and diagonal moves it goes round, cutting corners
+        let cheapest = a_star_search(start, end, &map);
+        let mut route = map.clone();
+        route.overlays.push(Overlay::path(&route, &cheapest.steps));
+        frames.record(
+            &route,
+            "Cheapest Route, Around Water and Rubble".to_string(),
+        );
+
+        // Find the path
+        let rects: Vec<Rect> = rooms.iter().map(|(r, _)| *r).collect();
+        let analysis = critical_path(&map, start, end, &rects).expect("Exit is unreachable");
+        for (i, r) in rects.iter().enumerate() {
+            let color = if analysis.rooms.contains(&i) {
+                RGB::named(YELLOW)
+            } else {
+                RGB::named(GRAY)
+            };
+            r.for_each(|p| {
+                let idx = map.idx(p.x, p.y);
+                if map.tiles[idx].0 != to_cp437('@') && map.tiles[idx].0 != to_cp437('>') {
+                    map.tiles[idx].1 = color;
+                }
+            });
+        }
+        frames.record(&map, "Important Rooms Highlighted".to_string());
+
+        // Shade side branches by how far they wander from the main path
+        for (i, t) in map.tiles.iter_mut().enumerate() {
+            if ![to_cp437('.'), to_cp437('@'), to_cp437('>')].contains(&t.0) {
+                if analysis.on_path(i) {
+                    t.1 = RGB::named(YELLOW);
+                } else {
+                    let d = analysis.distance[i];
+                    t.1 = RGB::from_f32(0.0, 0.0, f32::max(0.2, 1.0 - (d / 40.0)));
+                }
+            }
+        }
+        let deepest = analysis
+            .branches
+            .iter()
+            .map(|b| b.depth)
+            .fold(0.0, f32::max);
+        frames.record(
+            &map,
+            format!(
+                "{} Side Branches, Deepest is {:.0} Steps From the Path",
+                analysis.branches.len(),
+                deepest
+            ),
+        );
+    }
+}
@@ -0,0 +1,105 @@
+use backend::*;
+
+pub struct RoomBuilder {
+    entities: Vec<Entity>,
+}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            entities: Vec::new(),
+        })
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Spawning",
+        "Entities spawned in rooms from a weighted table",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rooms = Vec::<Rect>::new();
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        for _ in 0..param("rooms", 30) {
+            let room = Rect::with_size(
+                rng.range(1, map.width as i32 - 10),
+                rng.range(1, map.height as i32 - 10),
+                rng.range(2, 10),
+                rng.range(2, 10),
+            );
+            let mut overlap = false;
+            for r in rooms.iter() {
+                let mut r_grow = *r;
+                r_grow.x1 -= 1;
+                r_grow.y1 -= 1;
+                r_grow.x2 += 1;
+                r_grow.y2 += 1;
+                if r_grow.intersect(&room) {
+                    overlap = true;
+                }
+            }
+
+            if !overlap {
+                rooms.push(room);
+            }
+        }
+
+        // Sort it
+        rooms.sort_by_key(|a| a.x1);
+        for room in rooms.iter() {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), RGB::named(YELLOW));
+            });
+        }
+
+        // Corridors
+        for (i, room) in rooms.iter().enumerate().skip(1) {
+            let prev = rooms[i - 1].center();
+            let new = room.center();
+
+            if rng.range(0, 2) == 1 {
+                apply_horizontal_tunnel(prev.x, new.x, prev.y, &mut map);
+                apply_vertical_tunnel(prev.y, new.y, new.x, &mut map);
+            } else {
+                apply_vertical_tunnel(prev.y, new.y, prev.x, &mut map);
+                apply_horizontal_tunnel(prev.x, new.x, new.y, &mut map);
+            }
+        }
+
+        // Start in the left-most room
+        let start = match rooms.first() {
+            Some(room) => room.center(),
+            None => {
+                frames.record(&map, "No Rooms, So Nowhere to Start".to_string());
+                return;
+            }
+        };
+        map.set(start, to_cp437('@'), RGB::named(GOLD));
+        frames.record(&map, "Rooms with a Start".to_string());
+
+        // Spawn entities on top of the terrain
+        let rules = SpawnRules {
+            depth: 3,
+            start: map.idx(start.x, start.y),
+            min_distance: 8.0,
+            max_per_area: 3,
+        };
+        self.entities = spawn_in_rooms(&map, &rooms, SPAWN_TABLE, &rules, &mut rng);
+        frames.record(
+            &map,
+            format!("Spawned {} Entities (E to toggle)", self.entities.len()),
+        );
+    }
+
+    fn entities(&self) -> Vec<Entity> {
+        self.entities.clone()
+    }
+}
@@ -0,0 +1,6 @@
+use backend::*;
+use c22_spawning::*;
+
+fn main() -> BError {
+    run(RoomBuilder::new())
+}
@@ -0,0 +1,89 @@
+use backend::*;
+
+pub struct RoomBuilder {
+    entities: Vec<Entity>,
+}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            entities: Vec::new(),
+        })
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Spawning by Region",
+        "Caves have no rooms, so Voronoi regions spread the spawns out instead",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+        let mut map = Map::new();
+        random_fill(&mut map, param("fill", 55), &mut rng);
+        for _ in 0..10 {
+            cellular_iterate(&mut map);
+        }
+        map.cull_disconnected();
+        frames.record(&map, "Cellular Automata Map".to_string());
+
+        // Seed the regions on open floor, so none of them are empty
+        let floor: Vec<usize> = (0..map.tiles.len())
+            .filter(|idx| !map.is_wall(*idx))
+            .collect();
+        if floor.is_empty() {
+            frames.record(&map, "No Open Floor to Spawn On".to_string());
+            return;
+        }
+        // Every open tile needs a region to belong to
+        let n_regions = usize::max(1, param("regions", 16));
+        let seeds: Vec<Point> = (0..n_regions)
+            .map(|_| {
+                let idx = *rng.random_slice_entry(&floor).unwrap();
+                map.point(idx)
+            })
+            .collect();
+
+        // Every open tile belongs to its closest seed; walls belong to none
+        let mut membership = vec![usize::MAX; map.tiles.len()];
+        for idx in floor.iter() {
+            let my_pos = map.point(*idx);
+            membership[*idx] = seeds
+                .iter()
+                .enumerate()
+                .map(|(i, pos)| (i, DistanceAlg::Pythagoras.distance2d(my_pos, *pos)))
+                .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap())
+                .unwrap()
+                .0;
+        }
+        let start = find_placement(&map, &Placement::Nearest(Anchor::Center))
+            .expect("The floor isn't empty");
+        map.set(map.point(start), to_cp437('@'), RGB::named(GOLD));
+        let mut regions = map.clone();
+        regions.overlays.push(Overlay::Regions(membership.clone()));
+        frames.record(&regions, format!("{} Voronoi Regions", seeds.len()));
+
+        // Spawn entities on top of the terrain
+        let rules = SpawnRules {
+            depth: 3,
+            start,
+            min_distance: 8.0,
+            max_per_area: 2,
+        };
+        self.entities = spawn_in_regions(&map, &membership, SPAWN_TABLE, &rules, &mut rng);
+        frames.record(
+            &map,
+            format!("Spawned {} Entities (E to toggle)", self.entities.len()),
+        );
+    }
+
+    fn entities(&self) -> Vec<Entity> {
+        self.entities.clone()
+    }
+}
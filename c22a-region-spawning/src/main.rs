@@ -0,0 +1,6 @@
+use backend::*;
+use c22a_region_spawning::*;
+
+fn main() -> BError {
+    run(RoomBuilder::new())
+}
@@ -0,0 +1,105 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Locks and Keys",
+        "Locks on the critical path with keys that can be reached",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rooms = Vec::<Rect>::new();
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        for _ in 0..param("rooms", 50) {
+            let room = Rect::with_size(
+                rng.range(1, map.width as i32 - 10),
+                rng.range(1, map.height as i32 - 10),
+                rng.range(2, 10),
+                rng.range(2, 10),
+            );
+            let mut overlap = false;
+            for r in rooms.iter() {
+                let mut r_grow = *r;
+                r_grow.x1 -= 1;
+                r_grow.y1 -= 1;
+                r_grow.x2 += 1;
+                r_grow.y2 += 1;
+                if r_grow.intersect(&room) {
+                    overlap = true;
+                }
+            }
+
+            if !overlap {
+                rooms.push(room);
+            }
+        }
+
+        // Sort it
+        rooms.sort_by_key(|a| a.x1);
+        for room in rooms.iter() {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), RGB::named(GREEN));
+            });
+        }
+
+        // Join each room to a random earlier one, so we get a tree with side branches
+        for (i, room) in rooms.iter().enumerate().skip(1) {
+            let prev = rooms[rng.range(0, i)].center();
+            let new = room.center();
+
+            if rng.range(0, 2) == 1 {
+                apply_horizontal_tunnel(prev.x, new.x, prev.y, &mut map);
+                apply_vertical_tunnel(prev.y, new.y, new.x, &mut map);
+            } else {
+                apply_vertical_tunnel(prev.y, new.y, prev.x, &mut map);
+                apply_horizontal_tunnel(prev.x, new.x, new.y, &mut map);
+            }
+        }
+
+        // Start/end
+        let (start, end) = match (
+            find_placement(&map, &Placement::Nearest(Anchor::Left)),
+            find_placement(&map, &Placement::Nearest(Anchor::Right)),
+        ) {
+            (Ok(start), Ok(end)) => (start, end),
+            _ => {
+                frames.record(&map, "No Rooms, So No Start or End".to_string());
+                return;
+            }
+        };
+        let mut marked = map.clone();
+        marked.tiles[start] = (to_cp437('@'), RGB::named(GOLD));
+        marked.tiles[end] = (to_cp437('>'), RGB::named(GOLD));
+        frames.record(&marked, "Rooms with Start/End".to_string());
+
+        // Lock the critical path
+        let locks = place_locks(&mut map, start, end, &rooms, 3, &mut rng);
+        map.tiles[start] = (to_cp437('@'), RGB::named(GOLD));
+        map.tiles[end] = (to_cp437('>'), RGB::named(GOLD));
+        frames.record(&map, format!("Placed {} Locks and Keys", locks.len()));
+
+        // Prove it can be beaten
+        let caption = match solve_locks(&map, start, end, &locks) {
+            Some(order) => format!(
+                "Solvable: Keys Collected in Order {:?}",
+                order.iter().map(|i| i + 1).collect::<Vec<usize>>()
+            ),
+            None => "Unsolvable!".to_string(),
+        };
+        frames.record(&map, caption);
+    }
+}
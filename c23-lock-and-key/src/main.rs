@@ -0,0 +1,6 @@
+use backend::*;
+use c23_lock_and_key::*;
+
+fn main() -> BError {
+    run(RoomBuilder::new())
+}
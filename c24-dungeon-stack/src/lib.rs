@@ -0,0 +1,104 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Dungeon Stack",
+        "Five levels joined by stairs, rooms above and caves below",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+
+        // Rooms near the surface, caves further down
+        let dungeon = Dungeon::generate(
+            5,
+            |depth, rng| {
+                if depth < 3 {
+                    rooms_level(depth, rng)
+                } else {
+                    cave_level(depth, rng)
+                }
+            },
+            &mut rng,
+        );
+
+        match dungeon {
+            Ok(dungeon) => dungeon.record(frames),
+            Err(e) => frames.record(&Map::new(), format!("No Dungeon: {}", e)),
+        }
+    }
+}
+
+// Deeper levels try to fit more, smaller rooms
+fn rooms_level(depth: i32, rng: &mut RandomNumberGenerator) -> Map {
+    let mut map = Map::new();
+    let mut rooms = Vec::<Rect>::new();
+    let max_size = i32::max(4, 11 - depth * 2);
+    for _ in 0..10 + depth * 10 {
+        let room = Rect::with_size(
+            rng.range(1, map.width as i32 - max_size - 1),
+            rng.range(1, map.height as i32 - max_size - 1),
+            rng.range(2, max_size),
+            rng.range(2, max_size),
+        );
+        let mut overlap = false;
+        for r in rooms.iter() {
+            let mut r_grow = *r;
+            r_grow.x1 -= 1;
+            r_grow.y1 -= 1;
+            r_grow.x2 += 1;
+            r_grow.y2 += 1;
+            if r_grow.intersect(&room) {
+                overlap = true;
+            }
+        }
+
+        if !overlap {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), RGB::named(YELLOW));
+            });
+            rooms.push(room);
+        }
+    }
+
+    rooms.sort_by_key(|a| a.x1);
+    for (i, room) in rooms.iter().enumerate().skip(1) {
+        let prev = rooms[i - 1].center();
+        let new = room.center();
+
+        if rng.range(0, 2) == 1 {
+            apply_horizontal_tunnel(prev.x, new.x, prev.y, &mut map);
+            apply_vertical_tunnel(prev.y, new.y, new.x, &mut map);
+        } else {
+            apply_vertical_tunnel(prev.y, new.y, prev.x, &mut map);
+            apply_horizontal_tunnel(prev.x, new.x, new.y, &mut map);
+        }
+    }
+
+    map
+}
+
+// Deeper caves start out with more wall, so they're tighter
+fn cave_level(depth: i32, rng: &mut RandomNumberGenerator) -> Map {
+    let mut map = Map::new();
+    random_fill(&mut map, 45 + depth * 2, rng);
+
+    for _ in 0..10 {
+        cellular_iterate(&mut map);
+    }
+
+    map
+}
@@ -0,0 +1,6 @@
+use backend::*;
+use c24_dungeon_stack::*;
+
+fn main() -> BError {
+    run(RoomBuilder::new())
+}
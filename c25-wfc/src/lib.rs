@@ -0,0 +1,84 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Wave Function Collapse",
+        "New maps made from the patterns in a sample",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+
+        // Learn from a cellular automata cave
+        let mut sample = Map::new();
+        random_fill(&mut sample, param("fill", 55), &mut rng);
+        for _ in 0..param("iterations", 10) {
+            cellular_iterate(&mut sample);
+        }
+        frames.record(&sample, "Sample: Cellular Automata".to_string());
+
+        let wfc = WaveFunctionCollapse::from_map(&sample, 3);
+        match generate(wfc, &mut rng, frames) {
+            Ok((mut map, patterns)) => {
+                frames.record(&map, format!("WFC from {} Cave Patterns", patterns));
+                map.cull_disconnected();
+                frames.record(&map, "Culled".to_string());
+            }
+            Err(e) => frames.record(&sample, e.to_string()),
+        }
+
+        // Learn from a small hand-drawn template
+        let mut sample = Map::new();
+        apply_prefab(&mut sample, TEMPLATE, 10, Point::zero(), RGB::named(YELLOW));
+        frames.record(&sample, "Sample: Template".to_string());
+
+        let wfc = WaveFunctionCollapse::from_template(TEMPLATE, 10, 10, 3, RGB::named(YELLOW));
+        match generate(wfc, &mut rng, frames) {
+            Ok((mut map, patterns)) => {
+                frames.record(&map, format!("WFC from {} Template Patterns", patterns));
+                map.cull_disconnected();
+                frames.record(&map, "Culled".to_string());
+            }
+            Err(e) => frames.record(&Map::new(), e.to_string()),
+        }
+    }
+}
+
+// The map and how many patterns it was made from
+fn generate(
+    wfc: Result<WaveFunctionCollapse, WfcError>,
+    rng: &mut RandomNumberGenerator,
+    frames: &mut dyn SnapshotRecorder,
+) -> Result<(Map, usize), WfcError> {
+    let mut wfc = wfc?;
+    wfc.frame_every = Some(250);
+    let map = wfc.generate(rng, frames)?;
+    Ok((map, wfc.pattern_count()))
+}
+
+// Tiles with itself: the doors line up across the edges
+const TEMPLATE: &str = "
+####..####
+#........#
+#........#
+#........#
+..........
+..........
+#........#
+#........#
+#........#
+####..####
+";
@@ -0,0 +1,6 @@
+use backend::*;
+use c25_wfc::*;
+
+fn main() -> BError {
+    run(RoomBuilder::new())
+}
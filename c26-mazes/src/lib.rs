@@ -0,0 +1,89 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Mazes",
+        "Backtracker, Prim, Kruskal and Eller mazes, braids and wide corridors",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+
+        // Watch one being carved, then show the rest finished
+        let mut maze = Maze::new(MazeAlgorithm::RecursiveBacktracker);
+        maze.frame_every = Some(40);
+        let map = maze.build(&mut rng, frames);
+        frames.record(&map, "Recursive Backtracker".to_string());
+
+        for (algorithm, name) in [
+            (MazeAlgorithm::Prim, "Prim's Algorithm"),
+            (MazeAlgorithm::Kruskal, "Kruskal's Algorithm"),
+            (MazeAlgorithm::Eller, "Eller's Algorithm"),
+        ]
+        .iter()
+        {
+            let map = Maze::new(*algorithm).build(&mut rng, frames);
+            frames.record(&map, name.to_string());
+        }
+
+        let mut maze = Maze::new(MazeAlgorithm::RecursiveBacktracker);
+        maze.braid = 0.5;
+        let map = maze.build(&mut rng, frames);
+        frames.record(&map, "Braid Maze - Half the Dead Ends Removed".to_string());
+
+        let mut maze = Maze::new(MazeAlgorithm::Kruskal);
+        maze.corridor_width = 2;
+        maze.braid = 1.0;
+        let map = maze.build(&mut rng, frames);
+        frames.record(&map, "Wide Corridors, No Dead Ends".to_string());
+
+        // Rooms first, then a maze to fill in the gaps between them
+        let mut map = Map::new();
+        let mut rooms = Vec::<Rect>::new();
+        for _ in 0..param("rooms", 30) {
+            let room = Rect::with_size(
+                rng.range(1, map.width as i32 - 10),
+                rng.range(1, map.height as i32 - 10),
+                rng.range(3, 9),
+                rng.range(3, 9),
+            );
+            let mut overlap = false;
+            for r in rooms.iter() {
+                let mut r_grow = *r;
+                r_grow.x1 -= 2;
+                r_grow.y1 -= 2;
+                r_grow.x2 += 2;
+                r_grow.y2 += 2;
+                if r_grow.intersect(&room) {
+                    overlap = true;
+                }
+            }
+
+            if !overlap {
+                room.for_each(|p| {
+                    map.set(p, to_cp437('#'), RGB::named(YELLOW));
+                });
+                rooms.push(room);
+            }
+        }
+        frames.record(&map, "Rooms".to_string());
+
+        let mut maze = Maze::new(MazeAlgorithm::RecursiveBacktracker);
+        maze.braid = 0.25;
+        maze.fill(&mut map, &mut rng, frames);
+        frames.record(&map, "Rooms Joined by a Maze".to_string());
+    }
+}
@@ -0,0 +1,6 @@
+use backend::*;
+use c26_mazes::*;
+
+fn main() -> BError {
+    run(RoomBuilder::new())
+}
@@ -0,0 +1,69 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Room Shapes",
+        "Circles, crosses, L-shapes, blobs and pillared halls",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rooms = Vec::<(Box<dyn RoomShape>, usize)>::new();
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        let mut room_counter = 0;
+        for _ in 0..param("rooms", 30) {
+            let bounds = Rect::with_size(
+                rng.range(1, map.width as i32 - 13),
+                rng.range(1, map.height as i32 - 13),
+                rng.range(4, 12),
+                rng.range(4, 12),
+            );
+            let room = random_room_shape(bounds, &mut rng);
+
+            // Odd shapes can sit closer together than their bounds suggest
+            let overlap = rooms.iter().any(|(r, _)| r.intersects(room.as_ref(), 1));
+            if !overlap {
+                room.apply(&mut map, to_cp437('#'), iteration_color(room_counter));
+                rooms.push((room, room_counter));
+                room_counter += 1;
+                frames.record(&map, format!("Add room {}", room_counter));
+            } else {
+                let mut discard = map.clone();
+                room.apply(&mut discard, to_cp437('!'), iteration_color(666));
+                frames.record(&discard, "Discard Frame".to_string());
+            }
+        }
+
+        // Sort it
+        rooms.sort_by_key(|a| a.0.bounds().x1);
+
+        // Corridors join the centers, which are always inside the shape
+        for (i, room) in rooms.iter().enumerate().skip(1) {
+            let prev = rooms[i - 1].0.center();
+            let new = room.0.center();
+
+            if rng.range(0, 2) == 1 {
+                apply_horizontal_tunnel_walls_only(prev.x, new.x, prev.y, &mut map);
+                apply_vertical_tunnel_walls_only(prev.y, new.y, new.x, &mut map);
+            } else {
+                apply_vertical_tunnel_walls_only(prev.y, new.y, prev.x, &mut map);
+                apply_horizontal_tunnel_walls_only(prev.x, new.x, new.y, &mut map);
+            }
+            frames.record(&map, format!("Corridor {}", i));
+        }
+    }
+}
@@ -0,0 +1,6 @@
+use backend::*;
+use c27_room_shapes::*;
+
+fn main() -> BError {
+    run(RoomBuilder::new())
+}
@@ -0,0 +1,49 @@
+use backend::*;
+
+pub struct RoomBuilder {}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {})
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "BSP Interior",
+        "A building carved into rooms that share their walls",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        // Partition the whole map; the outer edge is the building's wall
+        let interior = BspInterior::new(
+            Rect::with_exact(0, 0, map.width as i32 - 1, map.height as i32 - 1),
+            4,
+            0.25,
+            &mut rng,
+        );
+
+        let mut rooms = interior.rooms();
+        rooms.sort_by_key(|a| a.x1);
+        for (i, room) in rooms.iter().enumerate() {
+            room.for_each(|p| {
+                map.set(p, to_cp437('#'), iteration_color(i));
+            });
+            frames.record(&map, "Add Room".to_string());
+        }
+
+        // Doors through the shared walls
+        for (i, door) in interior.doors.iter().enumerate() {
+            map.set(*door, to_cp437('+'), RGB::named(YELLOW));
+            frames.record(&map, format!("Door {}", i + 1));
+        }
+    }
+}
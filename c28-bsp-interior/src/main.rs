@@ -0,0 +1,6 @@
+use backend::*;
+use c28_bsp_interior::*;
+
+fn main() -> BError {
+    run(RoomBuilder::new())
+}
@@ -0,0 +1,56 @@
+use backend::*;
+
+pub struct RoomBuilder {
+    entities: Vec<Entity>,
+}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self {
+            entities: Vec::new(),
+        })
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Town",
+        "A walled town with shops, houses, roads and a way to the dungeon",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rng = new_rng();
+
+        let (map, town) = Town::generate(&mut rng, frames);
+
+        // Everyone but the householders minds their shop
+        self.entities = town
+            .buildings
+            .iter()
+            .filter(|b| b.kind != BuildingKind::House)
+            .map(|b| Entity {
+                name: b.kind.name().to_string(),
+                glyph: to_cp437('@'),
+                color: RGB::named(WHITE),
+                position: b.footprint.center(),
+            })
+            .collect();
+        frames.record(
+            &map,
+            format!(
+                "{} Buildings, {} Shopkeepers (E to toggle)",
+                town.buildings.len(),
+                self.entities.len()
+            ),
+        );
+    }
+
+    fn entities(&self) -> Vec<Entity> {
+        self.entities.clone()
+    }
+}
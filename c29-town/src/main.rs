@@ -0,0 +1,6 @@
+use backend::*;
+use c29_town::*;
+
+fn main() -> BError {
+    run(RoomBuilder::new())
+}
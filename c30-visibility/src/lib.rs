@@ -0,0 +1,112 @@
+use backend::*;
+
+pub struct RoomBuilder {
+    visibility: Option<Visibility>,
+}
+
+impl RoomBuilder {
+    pub fn new() -> Box<Self> {
+        Box::new(Self { visibility: None })
+    }
+}
+
+pub fn register(registry: &mut Registry) {
+    registry.register(
+        "Visibility",
+        "What can be seen from the start and each room, and where to lurk",
+        || RoomBuilder::new(),
+    );
+}
+
+impl MapGen for RoomBuilder {
+    fn setup(&mut self) {}
+
+    fn build(&mut self, frames: &mut dyn SnapshotRecorder) {
+        let mut rooms = Vec::<Rect>::new();
+        let mut rng = new_rng();
+        let mut map = Map::new();
+
+        for _ in 0..param("rooms", 30) {
+            let room = Rect::with_size(
+                rng.range(1, map.width as i32 - 12),
+                rng.range(1, map.height as i32 - 12),
+                rng.range(3, 12),
+                rng.range(3, 12),
+            );
+            let mut overlap = false;
+            for r in rooms.iter() {
+                let mut r_grow = *r;
+                r_grow.x1 -= 1;
+                r_grow.y1 -= 1;
+                r_grow.x2 += 1;
+                r_grow.y2 += 1;
+                if r_grow.intersect(&room) {
+                    overlap = true;
+                }
+            }
+
+            if !overlap {
+                room.for_each(|p| {
+                    map.set(p, to_cp437('#'), RGB::named(GREEN));
+                });
+                rooms.push(room);
+            }
+        }
+
+        rooms.sort_by_key(|a| a.x1);
+        for (i, room) in rooms.iter().enumerate().skip(1) {
+            let prev = rooms[i - 1].center();
+            let new = room.center();
+
+            if rng.range(0, 2) == 1 {
+                apply_horizontal_tunnel(prev.x, new.x, prev.y, &mut map);
+                apply_vertical_tunnel(prev.y, new.y, new.x, &mut map);
+            } else {
+                apply_vertical_tunnel(prev.y, new.y, prev.x, &mut map);
+                apply_horizontal_tunnel(prev.x, new.x, new.y, &mut map);
+            }
+        }
+        frames.record(&map, "Rooms and Corridors".to_string());
+
+        let (start, end) = match (
+            find_placement(&map, &Placement::Nearest(Anchor::Left)),
+            find_placement(&map, &Placement::Nearest(Anchor::Right)),
+        ) {
+            (Ok(start), Ok(end)) => (start, end),
+            _ => {
+                frames.record(&map, "No Rooms, So No Start or End".to_string());
+                return;
+            }
+        };
+        let path = a_star_search(start, end, &map);
+        let visibility = visibility_analysis(&map, start, &rooms, &path.steps);
+
+        // What each room can see
+        for (i, seen) in visibility.from_rooms.iter().enumerate() {
+            let mut view = map.clone();
+            for (idx, t) in view.tiles.iter_mut().enumerate() {
+                if !seen[idx] {
+                    t.1 = t.1.lerp(RGB::named(BLACK), 0.7);
+                }
+            }
+            let count = seen.iter().filter(|s| **s).count();
+            frames.record(&view, format!("Room {} Sees {} Tiles", i + 1, count));
+        }
+
+        map.tiles[start] = (to_cp437('@'), RGB::named(GOLD));
+        map.tiles[end] = (to_cp437('>'), RGB::named(GOLD));
+        frames.record(
+            &map,
+            format!(
+                "{:.0}% Visible from Start, {} Ambush Tiles (V to toggle)",
+                visibility.visible_fraction * 100.0,
+                visibility.ambush.len()
+            ),
+        );
+        self.visibility = Some(visibility);
+    }
+
+    fn visibility(&self) -> Option<Visibility> {
+        self.visibility.clone()
+    }
+}
@@ -0,0 +1,6 @@
+use backend::*;
+use c30_visibility::*;
+
+fn main() -> BError {
+    run(RoomBuilder::new())
+}
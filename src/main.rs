@@ -1,3 +1,93 @@
-fn main() {
-    println!("Hello, world!");
+use backend::*;
+
+const LEVELS_FILE: &str = "levels.toml";
+
+fn main() -> BError {
+    let mut options = CliOptions::from_args();
+    if options.help {
+        println!("{}", CliOptions::usage());
+        return Ok(());
+    }
+    if options.level.is_some() && options.script.is_some() {
+        eprintln!("--level and --script can't be used together");
+        std::process::exit(2);
+    }
+    if (options.headless || options.export.is_some()) && options.level.is_none() {
+        eprintln!(
+            "--headless and --export build a single chapter or level: cargo run -- --level \"Open Caverns\" --headless"
+        );
+        std::process::exit(2);
+    }
+
+    let mut registry = Registry::new();
+    c01_rooms::register(&mut registry);
+    c02_bsprooms::register(&mut registry);
+    c03_cellular::register(&mut registry);
+    c04_drunkard::register(&mut registry);
+    c05_dla::register(&mut registry);
+    c06_dla_attractor::register(&mut registry);
+    c07_dla_symmetry::register(&mut registry);
+    c08_dla_erosion::register(&mut registry);
+    c08a_voronoi::register(&mut registry);
+    c09_combination::register(&mut registry);
+    c10_perlin::register(&mut registry);
+    c11_overworld::register(&mut registry);
+    c12_noisezoom::register(&mut registry);
+    c13_double_noise::register(&mut registry);
+    c14_not_a_trap::register(&mut registry);
+    c15_still_not_a_trap::register(&mut registry);
+    c16_unreachable::register(&mut registry);
+    c17_starting_left::register(&mut registry);
+    c18_ending_right::register(&mut registry);
+    c19_ending_dijkstra::register(&mut registry);
+    c20_hot_path::register(&mut registry);
+    c21_hot_rooms::register(&mut registry);
+    c22_spawning::register(&mut registry);
+    c22a_region_spawning::register(&mut registry);
+    c23_lock_and_key::register(&mut registry);
+    c24_dungeon_stack::register(&mut registry);
+    c25_wfc::register(&mut registry);
+    c26_mazes::register(&mut registry);
+    c27_room_shapes::register(&mut registry);
+    c28_bsp_interior::register(&mut registry);
+    c29_town::register(&mut registry);
+    c30_visibility::register(&mut registry);
+
+    // Designers' levels and parameters, read fresh on every run: the --config
+    // file, or levels.toml if there isn't one
+    let config = match options.config.take() {
+        Some(config) => Some(config),
+        None if std::path::Path::new(LEVELS_FILE).exists() => Some(Config::load(LEVELS_FILE)?),
+        None => None,
+    };
+    if let Some(config) = config {
+        for (name, value) in config.params() {
+            options.params.entry(name).or_insert(value);
+        }
+        let chapter_params = config.chapter_params();
+        for level in config.levels {
+            registry.register_level(level);
+        }
+        for (name, params) in chapter_params {
+            registry.set_params(&name, params)?;
+        }
+    }
+
+    options.apply();
+
+    if let Some(name) = &options.level {
+        return match registry.find(name) {
+            Some(chapter) => chapter.run(&options),
+            None => {
+                eprintln!("There's no chapter or level called \"{}\"", name);
+                std::process::exit(2);
+            }
+        };
+    }
+
+    // Given a script, run the talk from it; otherwise pick chapters from a menu
+    match &options.script {
+        Some(script) => run_presentation(registry, load_script(script)?),
+        None => run_launcher(registry),
+    }
 }